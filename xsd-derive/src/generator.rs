@@ -1,11 +1,18 @@
-use std::path::Path;
+use std::path::PathBuf;
 
 use proc_macro2::TokenStream;
 use quote::quote;
+use xsd_internal::xsd::context::Options;
+use xsd_internal::xsd::resolver::SchemaResolver;
 use xsd_internal::xsd::schema::{Schema, SchemaError};
 
-pub fn generate(item: &syn::ItemMod, path: impl AsRef<Path>) -> Result<TokenStream, SchemaError> {
-    let schema = Schema::parse_file(path)?;
+pub fn generate(
+    item: &syn::ItemMod,
+    paths: &[PathBuf],
+    resolver: &dyn SchemaResolver,
+    options: Options,
+) -> Result<TokenStream, SchemaError> {
+    let schema = Schema::parse_files_with_resolver(paths, resolver)?.with_options(options);
     let structs = schema.generate_all()?;
 
     let attrs = &item.attrs;
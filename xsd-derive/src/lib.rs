@@ -6,16 +6,99 @@ use std::env;
 use std::path::PathBuf;
 
 use proc_macro::TokenStream;
+use xsd_internal::xsd::context::Options;
+use xsd_internal::xsd::resolver::DefaultResolver;
 
 #[proc_macro_attribute]
 pub fn all(args: TokenStream, item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::ItemMod);
 
-    let mut schema_path: Option<String> = None;
+    let mut schema_paths: Vec<String> = Vec::new();
+    let mut catalogs: Vec<String> = Vec::new();
+    let mut namespace_schemas: Vec<(String, String)> = Vec::new();
+    let mut options = Options::default();
+    let mut omit_default_attributes = None;
     let args_parser = syn::meta::parser(|meta| {
         if meta.path.is_ident("schema") {
+            // either a single path or a list of paths, each of which can be a glob pattern
+            let value = meta.value()?;
+            if value.peek(syn::token::Bracket) {
+                let content;
+                syn::bracketed!(content in value);
+                let paths = content
+                    .parse_terminated(<syn::LitStr as syn::parse::Parse>::parse, syn::Token![,])?;
+                schema_paths.extend(paths.iter().map(syn::LitStr::value));
+            } else {
+                let value: syn::LitStr = value.parse()?;
+                schema_paths.push(value.value());
+            }
+            Ok(())
+        } else if meta.path.is_ident("catalog") {
             let value: syn::LitStr = meta.value()?.parse()?;
-            schema_path = Some(value.value());
+            catalogs.push(value.value());
+            Ok(())
+        } else if meta.path.is_ident("import") {
+            // import(namespace = "...", schema = "...")
+            let mut namespace = None;
+            let mut schema = None;
+            meta.parse_nested_meta(|meta| {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("namespace") {
+                    namespace = Some(value.value());
+                } else if meta.path.is_ident("schema") {
+                    schema = Some(value.value());
+                } else {
+                    return Err(meta.error("unsupported import property"));
+                }
+                Ok(())
+            })?;
+            match (namespace, schema) {
+                (Some(namespace), Some(schema)) => {
+                    namespace_schemas.push((namespace, schema));
+                    Ok(())
+                }
+                _ => Err(meta.error("import requires `namespace` and `schema`")),
+            }
+        } else if meta.path.is_ident("module") {
+            // module(namespace = "...", name = "...")
+            let mut namespace = None;
+            let mut name = None;
+            meta.parse_nested_meta(|meta| {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("namespace") {
+                    namespace = Some(value.value());
+                } else if meta.path.is_ident("name") {
+                    syn::parse_str::<syn::Ident>(&value.value())
+                        .map_err(|_| syn::Error::new_spanned(&value, "invalid module name"))?;
+                    name = Some(value.value());
+                } else {
+                    return Err(meta.error("unsupported module property"));
+                }
+                Ok(())
+            })?;
+            match (namespace, name) {
+                (Some(namespace), Some(name)) => {
+                    options.namespace_modules.insert(namespace, name);
+                    Ok(())
+                }
+                _ => Err(meta.error("module requires `namespace` and `name`")),
+            }
+        } else if meta.path.is_ident("element_defaults") {
+            let value: syn::LitBool = meta.value()?.parse()?;
+            options.element_defaults = value.value();
+            Ok(())
+        } else if meta.path.is_ident("attribute_defaults") {
+            let value: syn::LitBool = meta.value()?.parse()?;
+            options.attribute_defaults = value.value();
+            Ok(())
+        } else if meta.path.is_ident("omit_default_attributes") {
+            let value: syn::LitBool = meta.value()?.parse()?;
+            options.omit_default_attributes = value.value();
+            omit_default_attributes = Some(value);
+            Ok(())
+        } else if meta.path.is_ident("xsi_type") {
+            let value: syn::LitBool = meta.value()?.parse()?;
+            options.xsi_type = value.value();
             Ok(())
         } else {
             Err(meta.error("unsupported property"))
@@ -26,15 +109,32 @@ pub fn all(args: TokenStream, item: TokenStream) -> TokenStream {
     // TODO: restrict to only one element attribute
     // TODO: validate provided arguments
 
-    let Some(schema_path) = schema_path else {
+    if let Some(value) =
+        omit_default_attributes.filter(|value| value.value() && !options.attribute_defaults)
+    {
+        return syn::Error::new_spanned(
+            value,
+            "`omit_default_attributes` requires `attribute_defaults = true`",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if schema_paths.is_empty() {
         return syn::Error::new_spanned(&input, "Argument `schema` required")
             .to_compile_error()
             .into();
-    };
-    generate(input, schema_path).unwrap_or_else(|e| e.to_compile_error().into())
+    }
+    generate(input, schema_paths, catalogs, namespace_schemas, options)
+        .unwrap_or_else(|e| e.to_compile_error().into())
 }
 
-fn generate(input: syn::ItemMod, schema_path: String) -> Result<TokenStream, syn::Error> {
+fn generate(
+    input: syn::ItemMod,
+    schema_paths: Vec<String>,
+    catalogs: Vec<String>,
+    namespace_schemas: Vec<(String, String)>,
+    options: Options,
+) -> Result<TokenStream, syn::Error> {
     // let sig = &input.sig;
     // let attrs = &input.attrs;
     // let vis = input.vis;
@@ -54,11 +154,39 @@ fn generate(input: syn::ItemMod, schema_path: String) -> Result<TokenStream, syn
     //     ));
     // }
 
-    let mut path = PathBuf::from(
+    let manifest_dir = PathBuf::from(
         env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR env to be defined"),
     );
-    path.push(&schema_path);
-    let generated_code = match generator::generate(&input, path) {
+    let mut paths = Vec::new();
+    for schema_path in schema_paths {
+        let path = manifest_dir.join(&schema_path);
+        if !schema_path.contains(['*', '?', '[']) {
+            paths.push(path);
+            continue;
+        }
+
+        let matches = glob::glob(&path.to_string_lossy())
+            .map_err(|err| syn::Error::new_spanned(&input, err))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| syn::Error::new_spanned(&input, err))?;
+        if matches.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &input,
+                format!("No schema file matches `{schema_path}`"),
+            ));
+        }
+        paths.extend(matches);
+    }
+    let mut resolver = DefaultResolver::new();
+    for catalog in catalogs {
+        resolver = resolver
+            .with_catalog(manifest_dir.join(catalog))
+            .map_err(|err| syn::Error::new_spanned(&input, err))?;
+    }
+    for (namespace, schema) in namespace_schemas {
+        resolver = resolver.with_namespace(namespace, manifest_dir.join(schema));
+    }
+    let generated_code = match generator::generate(&input, &paths, &resolver, options) {
         Ok(result) => result,
         Err(err) => return Err(syn::Error::new_spanned(input, err)),
     };
@@ -1,4 +1,4 @@
-use super::{LeafContent, Name};
+use super::{LeafContent, Name, Namespace, Wildcard};
 use crate::utils::escape_ident;
 use crate::xsd::context::SchemaContext;
 use inflector::Inflector;
@@ -14,11 +14,28 @@ pub struct Attribute {
     pub docs: Option<String>,
 }
 
+/// A top-level `<xs:attributeGroup>`, whose attributes are copied into the elements referencing it.
+#[derive(Debug, Clone, Default)]
+pub struct AttributeGroup {
+    pub attributes: Vec<Attribute>,
+    pub any_attribute: Option<Wildcard>,
+    /// Other attribute groups referenced by the group.
+    pub groups: Vec<Name>,
+}
+
 impl Attribute {
+    /// The `default` value of the attribute in case it is used to fill a missing attribute (see
+    /// [crate::xsd::context::Options::attribute_defaults]).
+    pub fn default_value(&self, ctx: &SchemaContext) -> Option<&str> {
+        self.default
+            .as_deref()
+            .filter(|_| ctx.options.attribute_defaults)
+    }
+
     pub fn to_impl(&self, ctx: &SchemaContext) -> TokenStream {
         let name_ident = escape_ident(&self.name.name.to_snake_case());
         let mut type_ident = self.content.to_impl(ctx);
-        if self.is_optional {
+        if self.is_optional && self.default_value(ctx).is_none() {
             type_ident = quote! { Option<#type_ident> };
         }
         let docs = self
@@ -35,18 +52,42 @@ impl Attribute {
 
     pub fn to_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
         let name_ident = escape_ident(&self.name.name.to_snake_case());
-        let name_xml = ctx.get_xml_element_name(&self.name);
+        let name_xml = ctx.get_xml_name(&self.name);
+        // the prefix of a qualified attribute is declared unless it is already in scope
+        let set_ns = match &self.name.namespace {
+            Namespace::None => TokenStream::new(),
+            Namespace::Id(id) => {
+                let ns = ctx.namespaces.get_by_id(*id);
+                let prefix = &ns.prefix;
+                let namespace = &ns.namespace;
+                quote! { ctx.set_ns(#prefix, #namespace); }
+            }
+        };
         let inner = match &self.content {
             LeafContent::Literal(literal) => literal.to_xml_impl(),
             LeafContent::Named(_) => quote! { val.to_string() },
             LeafContent::Fixed(fixed) => quote! { #fixed },
+            LeafContent::Wildcard(_) => unreachable!("wildcards only match elements"),
         };
-        if self.is_optional {
+        if let Some(default) = self
+            .default_value(ctx)
+            .filter(|_| ctx.options.omit_default_attributes)
+        {
+            let type_ident = self.content.to_impl(ctx);
             quote! {
-                let val = self.#name_ident.as_ref().map(|val| {
-                    #inner
-                });
-                if let Some(val) = val {
+                let val = &self.#name_ident;
+                let default: Option<#type_ident> = ::std::str::FromStr::from_str(#default).ok();
+                if default.as_ref() != Some(val) {
+                    let val = #inner;
+                    #set_ns
+                    ctx.set_attr(#name_xml, val);
+                }
+            }
+        } else if self.is_optional && self.default_value(ctx).is_none() {
+            quote! {
+                if let Some(val) = &self.#name_ident {
+                    let val = #inner;
+                    #set_ns
                     ctx.set_attr(#name_xml, val)
                 }
             }
@@ -54,20 +95,41 @@ impl Attribute {
             quote! {
                 let val = &self.#name_ident;
                 let val = #inner;
+                #set_ns
                 ctx.set_attr(#name_xml, val);
             }
         }
     }
 
-    pub fn from_xml_impl(&self) -> TokenStream {
+    pub fn from_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
         let name_ident = escape_ident(&self.name.name.to_snake_case());
         let name_xml = &self.name.name;
-        let inner = self.content.from_str_impl();
+        let inner = self.content.from_str_impl(ctx);
+        let (attribute, try_attribute) = match &self.name.namespace {
+            Namespace::None => (
+                quote!(node.attribute(#name_xml)),
+                quote!(node.try_attribute(#name_xml)),
+            ),
+            Namespace::Id(id) => {
+                let namespace = &ctx.namespaces.get_by_id(*id).namespace;
+                (
+                    quote!(node.attribute_ns(#name_xml, #namespace)),
+                    quote!(node.try_attribute_ns(#name_xml, #namespace)),
+                )
+            }
+        };
 
-        if self.is_optional {
+        if let Some(default) = self.default_value(ctx) {
+            quote! {
+                #name_ident: {
+                    let val = #attribute.unwrap_or(#default);
+                    #inner
+                },
+            }
+        } else if self.is_optional {
             quote! {
                 #name_ident: {
-                    if let Some(val) = node.attribute(#name_xml) {
+                    if let Some(val) = #attribute {
                         Some(#inner)
                     } else {
                         None
@@ -77,7 +139,7 @@ impl Attribute {
         } else {
             quote! {
                 #name_ident: {
-                    let val = node.try_attribute(#name_xml)?;
+                    let val = #try_attribute?;
                     #inner
                 },
             }
@@ -1,7 +1,8 @@
-use crate::ast::{LeafContent, LeafDefinition};
+use crate::ast::{LeafContent, LeafDefinition, Root};
 use crate::xsd::context::SchemaContext;
+use std::collections::{HashMap, HashSet};
 
-use super::{Attribute, ElementContent};
+use super::{Attribute, ElementContent, Name, Wildcard};
 use proc_macro2::TokenStream;
 use quote::quote;
 use quote::TokenStreamExt;
@@ -11,6 +12,9 @@ pub struct ElementDefinition {
     pub attributes: Vec<Attribute>,
     pub content: Option<ElementContent>,
     pub is_virtual: bool,
+    pub is_mixed: bool,
+    /// The `<xs:anyAttribute>` wildcard, whose matches are kept in an `other_attributes` map.
+    pub any_attribute: Option<Wildcard>,
     pub docs: Option<String>,
 }
 
@@ -22,6 +26,49 @@ impl ElementDefinition {
         self
     }
 
+    /// The definitions of the extended base types and of the virtual types, which decode their
+    /// content and attributes from the same element (by name, transitively).
+    pub fn bases<'a>(
+        &'a self,
+        elements: &'a HashMap<Name, Root>,
+    ) -> Vec<(&'a Name, &'a ElementDefinition)> {
+        let mut bases = Vec::new();
+        let mut pending = vec![self];
+        let mut visited = HashSet::new();
+        while let Some(def) = pending.pop() {
+            let contents: Vec<_> = match &def.content {
+                Some(ElementContent::Leaf(_, definition)) => vec![&definition.content],
+                Some(ElementContent::Leaves(leaves)) => leaves
+                    .iter()
+                    .filter(|leaf| leaf.is_virtual)
+                    .map(|leaf| &leaf.definition.content)
+                    .collect(),
+                None => Vec::new(),
+            };
+            for content in contents {
+                if let LeafContent::Named(name) = content {
+                    if let Some(Root::Element(base)) = elements.get(name) {
+                        if visited.insert(name) {
+                            bases.push((name, base));
+                            pending.push(base);
+                        }
+                    }
+                }
+            }
+        }
+        bases
+    }
+
+    /// All attributes decoded from the element, including those of the extended base type (which
+    /// decodes its attributes from the same element).
+    pub fn decoded_attributes<'a>(&'a self, ctx: &'a SchemaContext) -> Vec<&'a Attribute> {
+        let mut attributes = self.attributes.iter().collect::<Vec<_>>();
+        for (_, base) in self.bases(&ctx.elements) {
+            attributes.extend(&base.attributes);
+        }
+        attributes
+    }
+
     pub fn to_impl(&self, ctx: &SchemaContext) -> TokenStream {
         let mut ts = TokenStream::new();
         if let Some(content) = &self.content {
@@ -30,6 +77,11 @@ impl ElementDefinition {
         for attr in &self.attributes {
             ts.append_all(attr.to_impl(ctx));
         }
+        if self.any_attribute.is_some() {
+            ts.append_all(quote! {
+                pub other_attributes: ::std::collections::BTreeMap<::xsd::QualifiedName, String>,
+            });
+        }
         ts
     }
 
@@ -38,6 +90,16 @@ impl ElementDefinition {
         for attr in &self.attributes {
             ts.append_all(attr.to_xml_impl(ctx));
         }
+        if self.any_attribute.is_some() {
+            ts.append_all(quote! {
+                for (name, val) in &self.other_attributes {
+                    if let (Some(prefix), Some(ns)) = (&name.prefix, &name.namespace) {
+                        ctx.set_ns(prefix.as_str(), ns.as_str());
+                    }
+                    ctx.set_attr(name, val.as_str());
+                }
+            });
+        }
 
         // let the base element (<xs:extension base="" />) do the wrapping so that the base type
         // is still able to add attributes to the XML element
@@ -54,6 +116,11 @@ impl ElementDefinition {
         let wrap = !self.is_virtual && !is_named_leaf;
         if wrap {
             ts.append_all(quote! { ctx.write_start_element(writer)?; });
+            if self.is_mixed {
+                // Writing (empty) text prevents the writer from indenting the children, which
+                // would otherwise add whitespace to the mixed content.
+                ts.append_all(quote! { writer.write(XmlEvent::characters(""))?; });
+            }
         }
         if let Some(content) = &self.content {
             ts.append_all(content.to_xml_impl(ctx));
@@ -70,7 +137,18 @@ impl ElementDefinition {
             ts.append_all(content.from_xml_impl(ctx));
         }
         for attr in &self.attributes {
-            ts.append_all(attr.from_xml_impl());
+            ts.append_all(attr.from_xml_impl(ctx));
+        }
+        if let Some(wildcard) = &self.any_attribute {
+            let declared = self.decoded_attributes(ctx).into_iter().map(|attr| {
+                let name_xml = &attr.name.name;
+                let namespace_xml = ctx.quote_xml_namespace(&attr.name);
+                quote!((#name_xml, #namespace_xml))
+            });
+            let matcher = wildcard.to_matcher_impl();
+            ts.append_all(quote! {
+                other_attributes: node.other_attributes(&[#(#declared),*], #matcher),
+            });
         }
         quote! {
             {
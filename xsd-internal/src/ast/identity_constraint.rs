@@ -0,0 +1,137 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::Name;
+use crate::xsd::context::SchemaContext;
+
+/// An `<xs:key>`, `<xs:keyref>` or `<xs:unique>` declared on an element.
+#[derive(Debug, Clone)]
+pub struct IdentityConstraint {
+    pub name: Name,
+    pub kind: IdentityKind,
+    /// The element the constraint is declared on, which is the scope of the constraint.
+    pub element: Name,
+    pub selector: XPath,
+    pub fields: Vec<XPath>,
+}
+
+#[derive(Debug, Clone)]
+pub enum IdentityKind {
+    Key,
+    Unique,
+    KeyRef { refer: Name },
+}
+
+/// The restricted XPath subset allowed in selectors and fields, e.g. `.//item | list/entry`.
+#[derive(Debug, Clone)]
+pub struct XPath {
+    pub xpath: String,
+    pub paths: Vec<XPathPath>,
+}
+
+#[derive(Debug, Clone)]
+pub struct XPathPath {
+    /// Whether the path starts with `.//`.
+    pub is_descendant: bool,
+    pub steps: Vec<NameTest>,
+    /// The final `@attribute` step (only allowed in fields).
+    pub attribute: Option<NameTest>,
+}
+
+/// A name test, with the namespace prefixes already resolved to their URIs.
+#[derive(Debug, Clone)]
+pub enum NameTest {
+    /// `*`
+    Any,
+    /// `prefix:*`
+    Namespace(Option<String>),
+    Name(String, Option<String>),
+}
+
+impl IdentityConstraint {
+    pub fn to_impl(&self, ctx: &SchemaContext) -> TokenStream {
+        let name = &self.name.name;
+        let namespace = ctx.quote_xml_namespace(&self.name);
+        let kind = match &self.kind {
+            IdentityKind::Key => quote!(::xsd::identity::IdentityKind::Key),
+            IdentityKind::Unique => quote!(::xsd::identity::IdentityKind::Unique),
+            IdentityKind::KeyRef { refer } => {
+                let refer_name = &refer.name;
+                let refer_namespace = ctx.quote_xml_namespace(refer);
+                quote! {
+                    ::xsd::identity::IdentityKind::KeyRef {
+                        refer: (#refer_name, #refer_namespace),
+                    }
+                }
+            }
+        };
+        let element_name = &self.element.name;
+        let element_namespace = ctx.quote_xml_namespace(&self.element);
+        let selector = self.selector.to_impl();
+        let fields = self.fields.iter().map(XPath::to_impl);
+
+        quote! {
+            ::xsd::identity::IdentityConstraint {
+                name: (#name, #namespace),
+                kind: #kind,
+                element: (#element_name, #element_namespace),
+                selector: #selector,
+                fields: &[#(#fields),*],
+            }
+        }
+    }
+}
+
+impl XPath {
+    fn to_impl(&self) -> TokenStream {
+        let xpath = &self.xpath;
+        let paths = self.paths.iter().map(|path| {
+            let is_descendant = path.is_descendant;
+            let steps = path.steps.iter().map(NameTest::to_impl);
+            let attribute = match &path.attribute {
+                Some(test) => {
+                    let test = test.to_impl();
+                    quote!(Some(#test))
+                }
+                None => quote!(None),
+            };
+            quote! {
+                ::xsd::identity::Path {
+                    is_descendant: #is_descendant,
+                    steps: &[#(#steps),*],
+                    attribute: #attribute,
+                }
+            }
+        });
+
+        quote! {
+            ::xsd::identity::XPath {
+                xpath: #xpath,
+                paths: &[#(#paths),*],
+            }
+        }
+    }
+}
+
+impl NameTest {
+    fn to_impl(&self) -> TokenStream {
+        fn quote_namespace(namespace: &Option<String>) -> TokenStream {
+            match namespace {
+                Some(namespace) => quote!(Some(#namespace)),
+                None => quote!(None),
+            }
+        }
+
+        match self {
+            NameTest::Any => quote!(::xsd::identity::NameTest::Any),
+            NameTest::Namespace(namespace) => {
+                let namespace = quote_namespace(namespace);
+                quote!(::xsd::identity::NameTest::Namespace(#namespace))
+            }
+            NameTest::Name(name, namespace) => {
+                let namespace = quote_namespace(namespace);
+                quote!(::xsd::identity::NameTest::Name(#name, #namespace))
+            }
+        }
+    }
+}
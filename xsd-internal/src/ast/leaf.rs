@@ -1,4 +1,4 @@
-use super::{LeafContent, LeafDefinition, Name};
+use super::{inlined, LeafContent, LeafDefinition, Name};
 use crate::ast::Root;
 use crate::utils::escape_ident;
 use crate::xsd::context::SchemaContext;
@@ -12,8 +12,11 @@ pub struct Leaf {
     pub definition: LeafDefinition,
     pub is_unordered: bool,
     pub is_virtual: bool,
+    /// Whether the element can be explicitly empty (`nillable="true"`).
+    pub is_nillable: bool,
     pub min_occurs: MinOccurs,
     pub max_occurs: MaxOccurs,
+    pub default: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -47,13 +50,116 @@ impl Leaf {
         }
     }
 
+    /// The `default` value of the element in case it is used to fill an empty (or an absent
+    /// optional) element (see [crate::xsd::context::Options::element_defaults]).
+    pub fn default_value(&self, ctx: &SchemaContext) -> Option<&str> {
+        if !ctx.options.element_defaults || self.is_virtual || self.is_nillable || self.is_vec() {
+            return None;
+        }
+
+        // defaults are only allowed for elements with simple content
+        let is_simple = match &self.definition.content {
+            LeafContent::Literal(_) => true,
+            LeafContent::Named(name) => {
+                matches!(
+                    ctx.resolve(name),
+                    Some(Root::Leaf(_) | Root::Enum(_) | Root::Union(_) | Root::List(_))
+                )
+            }
+            LeafContent::Fixed(_) | LeafContent::Wildcard(_) => false,
+        };
+        self.default.as_deref().filter(|_| is_simple)
+    }
+
+    /// Whether the next child element is matched by the leaf.
+    pub fn peek_child_impl(&self, ctx: &SchemaContext) -> TokenStream {
+        match &self.definition.content {
+            LeafContent::Wildcard(wildcard) => {
+                let matcher = wildcard.to_matcher_impl();
+                quote!(node.peek_any_child(#matcher))
+            }
+            _ => {
+                let name_xml = &self.name.name;
+                let namespace_xml = ctx.quote_xml_namespace(&self.name);
+                quote!(node.peek_child(#name_xml, #namespace_xml))
+            }
+        }
+    }
+
+    /// The next child element if it is matched by the leaf (`node.next_child` or
+    /// `node.try_next_child` depending on `method`).
+    pub fn next_child_impl(&self, method: &str, ctx: &SchemaContext) -> TokenStream {
+        match &self.definition.content {
+            LeafContent::Wildcard(wildcard) => {
+                // wildcards are only allowed in sequences and choices, which are ordered
+                let method = if method.starts_with("try_") {
+                    quote!(try_next_any_child)
+                } else {
+                    quote!(next_any_child)
+                };
+                let matcher = wildcard.to_matcher_impl();
+                quote!(node.#method(#matcher))
+            }
+            _ => {
+                let method = format_ident!("{}", method);
+                let name_xml = &self.name.name;
+                let namespace_xml = ctx.quote_xml_namespace(&self.name);
+                quote!(node.#method(#name_xml, #namespace_xml))
+            }
+        }
+    }
+
+    /// The type of a single occurrence of the element.
+    pub fn element_type_impl(&self, ctx: &SchemaContext) -> TokenStream {
+        let type_ident = self.definition.to_impl(ctx);
+        if self.is_nillable {
+            quote! { ::xsd::Nillable<#type_ident> }
+        } else {
+            type_ident
+        }
+    }
+
+    /// Decodes a single occurrence of the element (the element is available as `node`).
+    pub fn nil_from_xml_impl(&self, value: TokenStream) -> TokenStream {
+        if !self.is_nillable {
+            return value;
+        }
+        quote! {
+            if node.is_nil() {
+                ::xsd::Nillable::Nil
+            } else {
+                ::xsd::Nillable::Value(#value)
+            }
+        }
+    }
+
+    /// Encodes a single occurrence of the element given by `val`, using `body` to write the value
+    /// of non-nil elements.
+    pub fn nil_to_xml_impl(&self, body: TokenStream) -> TokenStream {
+        if !self.is_nillable {
+            return body;
+        }
+        quote! {
+            match val {
+                ::xsd::Nillable::Nil => {
+                    ctx.set_nil();
+                    ctx.write_start_element(writer)?;
+                    ctx.write_end_element(writer)?;
+                }
+                ::xsd::Nillable::Value(val) => {
+                    #body
+                }
+            }
+        }
+    }
+
     pub fn to_impl(&self, ctx: &SchemaContext) -> TokenStream {
         let name_ident = escape_ident(&self.name.name.to_snake_case());
-        let mut type_ident = self.definition.to_impl(ctx);
+        let mut type_ident = self.element_type_impl(ctx);
         if self.is_vec() {
             type_ident = quote! { Vec<#type_ident> }
         }
-        if self.is_optional() {
+        if self.is_optional() && self.default_value(ctx).is_none() {
             type_ident = quote! { Option<#type_ident> }
         }
         let docs = self
@@ -71,49 +177,55 @@ impl Leaf {
 
     pub fn to_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
         let name_ident = escape_ident(&self.name.name.to_snake_case());
-        let name_xml = ctx.get_xml_element_name(&self.name);
+        let name_xml = ctx.get_xml_name(&self.name);
+        let namespace_xml = ctx.quote_xml_namespace(&self.name);
         let inner = self.definition.to_xml_impl(ctx);
 
         let mut tn = TokenStream::new();
-        if self.is_virtual {
-            tn.append_all(quote! {
-                let mut ctx = ::xsd::Context::wrap(&mut ctx);
-            });
+        if matches!(self.definition.content, LeafContent::Wildcard(_)) {
+            // the captured element is written as is
+            tn.append_all(inner);
         } else {
-            tn.append_all(quote! {
-                let mut ctx = ::xsd::Context::new(#name_xml);
-            });
-        }
+            if self.is_virtual {
+                tn.append_all(quote! {
+                    let mut ctx = ::xsd::Context::wrap(&mut ctx);
+                });
+            } else {
+                tn.append_all(quote! {
+                    let mut ctx = ctx.child(#name_xml, #namespace_xml);
+                });
+            }
 
-        let wrap = !self.is_virtual
-            && match &self.definition {
-                LeafDefinition {
-                    content: LeafContent::Named(name),
-                    ..
-                } => {
-                    matches!(
-                        ctx.resolve(name),
-                        Some(Root::Leaf(LeafDefinition {
-                            content: LeafContent::Literal(_),
-                            ..
-                        }))
-                    )
-                }
-                _ => true,
-            };
+            let mut body = TokenStream::new();
+            // inlined simple types only write the text, other types write the element themselves
+            let mut content = &self.definition.content;
+            while let Some(def) = match content {
+                LeafContent::Named(name) => inlined(name, ctx),
+                _ => None,
+            } {
+                content = &def.content;
+            }
+            let wrap = !self.is_virtual
+                && match &self.definition.content {
+                    LeafContent::Named(_) => matches!(content, LeafContent::Literal(_)),
+                    _ => true,
+                };
 
-        if wrap {
-            tn.append_all(quote! {
-                ctx.write_start_element(writer)?;
-            });
-        }
+            if wrap {
+                body.append_all(quote! {
+                    ctx.write_start_element(writer)?;
+                });
+            }
 
-        tn.append_all(inner);
+            body.append_all(inner);
 
-        if wrap {
-            tn.append_all(quote! {
-                ctx.write_end_element(writer)?;
-            })
+            if wrap {
+                body.append_all(quote! {
+                    ctx.write_end_element(writer)?;
+                })
+            }
+
+            tn.append_all(self.nil_to_xml_impl(body));
         }
 
         if self.is_vec() {
@@ -123,7 +235,7 @@ impl Leaf {
                 }
             };
         }
-        if self.is_optional() {
+        if self.is_optional() && self.default_value(ctx).is_none() {
             tn = quote! {
                 if let Some(val) = val {
                     #tn
@@ -141,9 +253,7 @@ impl Leaf {
 
     pub fn from_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
         let name_ident = escape_ident(&self.name.name.to_snake_case());
-        let name_xml = &self.name.name;
-        let namespace_xml = ctx.quote_xml_namespace(&self.name);
-        let mut value = self.definition.from_xml_impl(ctx);
+        let mut value = self.nil_from_xml_impl(self.definition.from_xml_impl(ctx));
 
         if self.is_virtual {
             if self.is_vec() {
@@ -155,13 +265,18 @@ impl Leaf {
                     unreachable!()
                 };
 
-                let first_name = format_ident!("{}", name.name.to_pascal_case());
+                let first_name = ctx.type_path(name);
+                let check = match ctx.elements.get(name) {
+                    Some(Root::Choice(def)) => def.check_occurrences_impl(&first_name, ctx),
+                    _ => TokenStream::new(),
+                };
                 value = quote! {
                     {
                         let mut vec = Vec::new();
                         while #first_name::lookahead(node) {
                             vec.push(#value);
                         }
+                        #check
                         vec
                     }
                 };
@@ -185,7 +300,7 @@ impl Leaf {
                     unreachable!()
                 };
 
-                let first_name = format_ident!("{}", name.name.to_pascal_case());
+                let first_name = ctx.type_path(name);
                 value = quote! {
                     if #first_name::lookahead(node) {
                         Some(#value)
@@ -195,11 +310,36 @@ impl Leaf {
                 };
             }
         } else {
-            value = if self.is_vec() {
+            value = if let Some(default) = self.default_value(ctx) {
+                let default_value = self.definition.from_str_impl(ctx);
+                let method = match (self.is_unordered, self.is_optional()) {
+                    (true, true) => "child",
+                    (true, false) => "try_child",
+                    (false, true) => "next_child",
+                    (false, false) => "try_next_child",
+                };
+                let child = self.next_child_impl(method, ctx);
+                // required elements must be present, even if only empty
+                let child = if self.is_optional() {
+                    quote!(#child)
+                } else {
+                    quote!(Some(#child?))
+                };
+                quote! {
+                    match #child {
+                        Some(node) if !node.text()?.is_empty() => #value,
+                        _ => {
+                            let val = #default;
+                            #default_value
+                        }
+                    }
+                }
+            } else if self.is_vec() {
+                let next_child = self.next_child_impl("next_child", ctx);
                 let mut from_vec = quote! {
                     {
                         let mut vec = Vec::new();
-                        while let Some(node) = node.next_child(#name_xml, #namespace_xml) {
+                        while let Some(node) = #next_child {
                             vec.push(#value);
                         }
                         vec
@@ -219,21 +359,24 @@ impl Leaf {
 
                 from_vec
             } else if self.is_optional() {
+                let next_child = self.next_child_impl("next_child", ctx);
                 quote! {
-                    if let Some(node) = node.next_child(#name_xml, #namespace_xml) {
+                    if let Some(node) = #next_child {
                         Some(#value)
                     } else {
                         None
                     }
                 }
             } else if self.is_unordered {
+                let child = self.next_child_impl("try_child", ctx);
                 quote! {
-                    let node = node.try_child(#name_xml, #namespace_xml)?;
+                    let node = #child?;
                     #value
                 }
             } else {
+                let next_child = self.next_child_impl("try_next_child", ctx);
                 quote! {
-                    let node = node.try_next_child(#name_xml, #namespace_xml)?;
+                    let node = #next_child?;
                     #value
                 }
             };
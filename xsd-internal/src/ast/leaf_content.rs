@@ -1,4 +1,6 @@
-use super::{LiteralType, Name};
+use std::collections::HashMap;
+
+use super::{LeafDefinition, LiteralType, Name, Wildcard};
 use crate::ast::Root;
 use crate::xsd::context::SchemaContext;
 use proc_macro2::TokenStream;
@@ -9,17 +11,29 @@ pub enum LeafContent {
     Literal(LiteralType),
     Named(Name),
     Fixed(String),
+    /// Any element matched by an `<xs:any>` wildcard, captured as raw XML.
+    Wildcard(Wildcard),
 }
 
 impl LeafContent {
+    /// Replaces a reference to one of the keys of `renames` by the corresponding value.
+    pub fn rename(&mut self, renames: &HashMap<Name, Name>) {
+        if let LeafContent::Named(name) = self {
+            if let Some(to) = renames.get(name) {
+                *name = to.clone();
+            }
+        }
+    }
+
     pub fn to_impl(&self, ctx: &SchemaContext) -> TokenStream {
         match self {
             LeafContent::Literal(literal) => literal.to_impl(),
-            LeafContent::Named(name) => match ctx.elements.get(name) {
-                Some(Root::Leaf(def)) => def.to_impl(ctx),
-                _ => name.to_impl(),
+            LeafContent::Named(name) => match inlined(name, ctx) {
+                Some(def) => def.to_impl(ctx),
+                None => ctx.type_path(name),
             },
             LeafContent::Fixed(_) => quote!(()),
+            LeafContent::Wildcard(_) => quote!(::xsd::AnyElement),
         }
     }
 
@@ -34,13 +48,16 @@ impl LeafContent {
                     }
                 }
             }
-            LeafContent::Named(name) => match ctx.elements.get(name) {
-                Some(Root::Leaf(def)) => def.to_xml_impl(ctx),
-                _ => name.to_xml_impl(),
+            LeafContent::Named(name) => match inlined(name, ctx) {
+                Some(def) => def.to_xml_impl(ctx),
+                None => name.to_xml_impl(),
             },
             LeafContent::Fixed(fixed) => quote! {
                 writer.write(XmlEvent::characters(#fixed))?;
             },
+            LeafContent::Wildcard(_) => quote! {
+                val.to_xml_writer(writer)?;
+            },
         }
     }
 
@@ -55,9 +72,9 @@ impl LeafContent {
                     }
                 }
             }
-            LeafContent::Named(name) => match ctx.elements.get(name) {
-                Some(Root::Leaf(def)) => def.from_xml_impl(ctx),
-                _ => name.from_xml_impl(),
+            LeafContent::Named(name) => match inlined(name, ctx) {
+                Some(def) => def.from_xml_impl(ctx),
+                None => name.from_xml_impl(ctx),
             },
             LeafContent::Fixed(fixed) => {
                 quote! {
@@ -72,14 +89,30 @@ impl LeafContent {
                     }
                 }
             }
+            LeafContent::Wildcard(_) => quote! {
+                ::xsd::AnyElement::from_xml_node(&node)
+            },
         }
     }
 
-    pub fn from_str_impl(&self) -> TokenStream {
+    pub fn from_str_impl(&self, ctx: &SchemaContext) -> TokenStream {
         match self {
             LeafContent::Literal(literal) => literal.from_str_impl(),
-            LeafContent::Named(name) => name.from_str_impl(),
+            LeafContent::Named(name) => match inlined(name, ctx) {
+                Some(def) => def.from_str_impl(ctx),
+                None => name.from_str_impl(),
+            },
             LeafContent::Fixed(_) => quote! { () },
+            LeafContent::Wildcard(_) => unreachable!("wildcards only match elements"),
         }
     }
 }
+
+/// The definition of the named simple type `name` if it is inlined into its usages. Types that
+/// restrict their values are kept as a newtype instead, which validates them on construction.
+pub fn inlined<'a>(name: &Name, ctx: &'a SchemaContext) -> Option<&'a LeafDefinition> {
+    match ctx.elements.get(name) {
+        Some(Root::Leaf(def)) if def.flatten(ctx).restrictions.is_empty() => Some(def),
+        _ => None,
+    }
+}
@@ -1,7 +1,13 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::ast::Root;
 use crate::xsd::context::SchemaContext;
 
-use super::LeafContent;
-use proc_macro2::TokenStream;
+use super::{LeafContent, LiteralType};
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 
 #[derive(Debug, Clone)]
@@ -29,11 +35,252 @@ impl LeafDefinition {
         self.content.to_impl(ctx)
     }
 
+    /// The definition with the literal type and the facets inherited from its named base types
+    /// (e.g. `<xs:restriction base="Price">` where `Price` restricts `xs:decimal`).
+    pub fn flatten(&self, ctx: &SchemaContext) -> Cow<'_, LeafDefinition> {
+        let mut def = Cow::Borrowed(self);
+        // circular types are rejected when parsing the schema, this only prevents an endless loop
+        let mut visited = HashSet::new();
+        while let LeafContent::Named(name) = &def.content {
+            let Some(Root::Leaf(base)) = ctx.elements.get(name) else {
+                break;
+            };
+            if !visited.insert(name.clone()) {
+                break;
+            }
+            let def = def.to_mut();
+            def.content = base.content.clone();
+            def.restrictions
+                .splice(0..0, base.restrictions.iter().cloned());
+        }
+        def
+    }
+
     pub fn to_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
-        self.content.to_xml_impl(ctx)
+        let inner = self.content.to_xml_impl(ctx);
+        if self.restrictions.is_empty() {
+            return inner;
+        }
+
+        // refuse to write values the schema would reject
+        let checks = self.validate_impl(ctx);
+        quote! {
+            #checks
+            #inner
+        }
     }
 
     pub fn from_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
-        self.content.from_xml_impl(ctx)
+        let inner = self.content.from_xml_impl(ctx);
+        self.with_validation(inner, ctx)
+    }
+
+    pub fn from_str_impl(&self, ctx: &SchemaContext) -> TokenStream {
+        let inner = self.content.from_str_impl(ctx);
+        self.with_validation(inner, ctx)
+    }
+
+    /// Checks the value `val` (a reference to the value) against all restrictions and returns a
+    /// `RestrictionError` (converted into the error type of the function) for the first one that
+    /// is violated.
+    pub fn validate_impl(&self, ctx: &SchemaContext) -> TokenStream {
+        let literal = self.literal_type(ctx);
+        let checks = self.restrictions.iter().map(|r| r.validate_impl(literal));
+        quote! {
+            #(#checks)*
+        }
+    }
+
+    fn with_validation(&self, inner: TokenStream, ctx: &SchemaContext) -> TokenStream {
+        if self.restrictions.is_empty() {
+            return inner;
+        }
+
+        let type_ident = self.to_impl(ctx);
+        let checks = self.validate_impl(ctx);
+        quote! {
+            {
+                let val: #type_ident = #inner;
+                {
+                    let val = &val;
+                    #checks
+                }
+                val
+            }
+        }
+    }
+
+    fn literal_type<'a>(&'a self, ctx: &'a SchemaContext) -> Option<&'a LiteralType> {
+        match &self.content {
+            LeafContent::Literal(literal) => Some(literal),
+            LeafContent::Named(name) => match ctx.resolve(name) {
+                Some(Root::Leaf(LeafDefinition {
+                    content: LeafContent::Literal(literal),
+                    ..
+                })) => Some(literal),
+                _ => None,
+            },
+            LeafContent::Fixed(_) | LeafContent::Wildcard(_) => None,
+        }
+    }
+}
+
+impl Restriction {
+    pub fn facet(&self) -> &'static str {
+        match self {
+            Restriction::Length(_) => "length",
+            Restriction::MinLength(_) => "minLength",
+            Restriction::MaxLength(_) => "maxLength",
+            Restriction::Pattern(_) => "pattern",
+            Restriction::MinInclusive(_) => "minInclusive",
+            Restriction::MaxInclusive(_) => "maxInclusive",
+            Restriction::FractionDigits(_) => "fractionDigits",
+            Restriction::TotalDigits(_) => "totalDigits",
+        }
+    }
+
+    /// Whether the facet can restrict a list (the length facets count its items).
+    pub fn applies_to_lists(&self) -> bool {
+        matches!(
+            self,
+            Restriction::Length(_)
+                | Restriction::MinLength(_)
+                | Restriction::MaxLength(_)
+                | Restriction::Pattern(_)
+        )
+    }
+
+    pub fn value(&self) -> String {
+        match self {
+            Restriction::Length(n)
+            | Restriction::MinLength(n)
+            | Restriction::MaxLength(n)
+            | Restriction::FractionDigits(n)
+            | Restriction::TotalDigits(n) => n.to_string(),
+            Restriction::Pattern(pattern) => pattern.clone(),
+            Restriction::MinInclusive(n) | Restriction::MaxInclusive(n) => n.to_string(),
+        }
+    }
+
+    /// Facets that do not apply to the given type (e.g. a `length` for a number) are ignored.
+    pub fn validate_impl(&self, literal: Option<&LiteralType>) -> TokenStream {
+        let facet = self.facet();
+        let restriction = self.value();
+        let error = quote! {
+            return Err(::xsd::restriction::RestrictionError {
+                facet: #facet,
+                restriction: #restriction,
+                value: val.to_string(),
+            }.into());
+        };
+
+        match self {
+            Restriction::Length(n) | Restriction::MinLength(n) | Restriction::MaxLength(n) => {
+                let Some(len) = literal.and_then(length_impl) else {
+                    return TokenStream::new();
+                };
+                let op = match self {
+                    Restriction::Length(_) => quote!(!=),
+                    Restriction::MinLength(_) => quote!(<),
+                    _ => quote!(>),
+                };
+                quote! {
+                    if #len #op #n {
+                        #error
+                    }
+                }
+            }
+            Restriction::Pattern(pattern) => {
+                let regex = crate::utils::translate_pattern(pattern);
+                quote! {
+                    {
+                        static PATTERN: ::std::sync::OnceLock<::xsd::regex::Regex> =
+                            ::std::sync::OnceLock::new();
+                        let pattern = PATTERN.get_or_init(|| {
+                            ::xsd::regex::Regex::new(#regex).expect("pattern validated by xsd")
+                        });
+                        if !pattern.is_match(&val.to_string()) {
+                            #error
+                        }
+                    }
+                }
+            }
+            Restriction::MinInclusive(bound) | Restriction::MaxInclusive(bound) => {
+                let is_min = matches!(self, Restriction::MinInclusive(_));
+                let op = if is_min { quote!(<) } else { quote!(>) };
+                let (val, bound) = match literal {
+                    Some(LiteralType::Int64 | LiteralType::Uint64 | LiteralType::Int32) => {
+                        let bound = if is_min { bound.ceil() } else { bound.floor() };
+                        let Some(bound) = bound.to_i128() else {
+                            return TokenStream::new();
+                        };
+                        (quote!(i128::from(*val)), int_literal(bound))
+                    }
+                    Some(LiteralType::Float32) => {
+                        let Some(bound) = bound.to_f64() else {
+                            return TokenStream::new();
+                        };
+                        (quote!(f64::from(*val)), quote!(#bound))
+                    }
+                    Some(LiteralType::Decimal) => {
+                        let mantissa = int_literal(bound.mantissa());
+                        let scale = bound.scale();
+                        (
+                            quote!(*val),
+                            quote!(rust_decimal::Decimal::from_i128_with_scale(#mantissa, #scale)),
+                        )
+                    }
+                    _ => return TokenStream::new(),
+                };
+                quote! {
+                    if #val #op #bound {
+                        #error
+                    }
+                }
+            }
+            Restriction::FractionDigits(n) | Restriction::TotalDigits(n) => {
+                if !matches!(
+                    literal,
+                    Some(
+                        LiteralType::Int64
+                            | LiteralType::Uint64
+                            | LiteralType::Int32
+                            | LiteralType::Decimal
+                            | LiteralType::Float32
+                    )
+                ) {
+                    return TokenStream::new();
+                }
+                let digits = if matches!(self, Restriction::TotalDigits(_)) {
+                    quote!(0)
+                } else {
+                    quote!(1)
+                };
+                quote! {
+                    if ::xsd::restriction::digits(&val.to_string()).#digits > #n {
+                        #error
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn length_impl(literal: &LiteralType) -> Option<TokenStream> {
+    use LiteralType::*;
+    match literal {
+        String | Any | DateTime | Time | Date | Duration => Some(quote!(val.chars().count())),
+        HexBinary => Some(quote!((val.len() / 2))),
+        Base64Binary => Some(quote!(::xsd::restriction::base64_length(val))),
+        Boolean | Int64 | Uint64 | Int32 | Decimal | Float32 => None,
+    }
+}
+
+fn int_literal(n: i128) -> TokenStream {
+    let lit = Literal::i128_unsuffixed(n.abs());
+    if n < 0 {
+        quote!(-#lit)
+    } else {
+        quote!(#lit)
     }
 }
@@ -1,6 +1,7 @@
 mod attribute;
 mod element_content;
 mod element_definition;
+mod identity_constraint;
 mod leaf;
 mod leaf_content;
 mod leaf_definition;
@@ -8,10 +9,12 @@ mod literal_type;
 mod name;
 mod namespaces;
 mod root;
+mod wildcard;
 
 pub use attribute::*;
 pub use element_content::*;
 pub use element_definition::*;
+pub use identity_constraint::*;
 pub use leaf::*;
 pub use leaf_content::*;
 pub use leaf_definition::*;
@@ -19,3 +22,4 @@ pub use literal_type::*;
 pub use name::*;
 pub use namespaces::*;
 pub use root::*;
+pub use wildcard::*;
@@ -1,4 +1,5 @@
 use crate::utils::escape_ident;
+use crate::xsd::context::SchemaContext;
 
 use inflector::Inflector;
 use proc_macro2::TokenStream;
@@ -36,10 +37,10 @@ impl Name {
         }
     }
 
-    pub fn from_xml_impl(&self) -> TokenStream {
-        let name_ident = escape_ident(&self.name.to_pascal_case());
+    pub fn from_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
+        let type_path = ctx.type_path(self);
         quote! {
-            #name_ident::from_xml_node(&node)?
+            #type_path::from_xml_node(&node)?
         }
     }
 
@@ -6,6 +6,8 @@ use super::Namespace;
 pub struct NamespaceDefinition {
     pub namespace: String,
     pub prefix: String,
+    /// Whether the local elements are in the namespace, too (`elementFormDefault="qualified"`).
+    pub is_qualified: bool,
 }
 
 #[derive(Debug, Default)]
@@ -26,6 +28,7 @@ impl Namespaces {
                 NamespaceDefinition {
                     namespace: namespace.to_string(),
                     prefix: format!("ns{}", id),
+                    is_qualified: false,
                 },
             );
             id
@@ -42,6 +45,14 @@ impl Namespaces {
         )
     }
 
+    pub fn set_qualified(&mut self, namespace: Namespace) {
+        if let Namespace::Id(id) = namespace {
+            if let Some(def) = self.by_id.get_mut(&id) {
+                def.is_qualified = true;
+            }
+        }
+    }
+
     pub fn get_by_id(&self, id: usize) -> &NamespaceDefinition {
         self.by_id
             .get(&id)
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use crate::xsd::context::SchemaContext;
 
-use super::{ElementContent, ElementDefinition, Leaf, LeafContent, LeafDefinition, Name};
+use super::{
+    ElementContent, ElementDefinition, Leaf, LeafContent, LeafDefinition, MaxOccurs, MinOccurs,
+    Name, Namespace, Restriction,
+};
 use inflector::Inflector;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, TokenStreamExt};
@@ -11,18 +14,73 @@ use syn::Ident;
 #[derive(Debug, Clone)]
 pub enum Root {
     Leaf(LeafDefinition),
-    Enum(Vec<Name>),
+    Enum(EnumDefinition),
     Element(ElementDefinition),
     Choice(ChoiceDefinition),
+    Union(UnionDefinition),
+    List(ListDefinition),
+    Derived(DerivedDefinition),
 }
 
 #[derive(Debug, Clone)]
 pub struct ChoiceDefinition {
     pub variants: Vec<Leaf>,
     pub is_virtual: bool,
+    /// Set if the choice is the content of a mixed complex type and thus also has a `Text`
+    /// variant for the text between the child elements.
+    pub mixed: Option<MixedContent>,
     pub docs: Option<String>,
 }
 
+/// The model group of a mixed complex type. Its child elements are decoded one at a time (in
+/// document order) as the variants of a choice, and only then checked against the model group.
+#[derive(Debug, Clone)]
+pub struct MixedContent {
+    pub group: ModelGroup,
+    pub min_occurs: MinOccurs,
+    pub max_occurs: MaxOccurs,
+    /// The declared occurrences of the variants.
+    pub occurs: Vec<(MinOccurs, MaxOccurs)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelGroup {
+    Sequence,
+    All,
+    Choice,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumDefinition {
+    pub variants: Vec<Name>,
+    /// The enum this enum is a subset of (a restriction of a named simple type).
+    pub base: Option<Name>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnionDefinition {
+    /// The member types (in the order they are tried when decoding) by the name of their variant.
+    pub variants: Vec<(Name, LeafContent)>,
+    pub docs: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListDefinition {
+    pub item: LeafContent,
+    /// The `length`, `minLength` and `maxLength` facets (which count the items) and `pattern`.
+    pub restrictions: Vec<Restriction>,
+    pub docs: Option<String>,
+}
+
+/// A complex type and all types derived from it, which can be used in its place by specifying an
+/// `xsi:type` attribute.
+#[derive(Debug, Clone)]
+pub struct DerivedDefinition {
+    pub base: Name,
+    /// The derived types (including types derived from those) in the order of their declaration.
+    pub derived: Vec<Name>,
+}
+
 impl Root {
     pub fn with_docs(mut self, docs: Option<String>) -> Self {
         if docs.is_some() {
@@ -31,13 +89,61 @@ impl Root {
                 Root::Enum(_) => {}
                 Root::Element(def) => def.docs = docs,
                 Root::Choice(def) => def.docs = docs,
+                Root::Union(def) => def.docs = docs,
+                Root::List(def) => def.docs = docs,
+                Root::Derived(_) => {}
             }
         }
         self
     }
 
+    /// Replaces all references to the keys of `renames` by the corresponding values.
+    pub fn rename_references(&mut self, renames: &HashMap<Name, Name>) {
+        let rename = |name: &mut Name| {
+            if let Some(to) = renames.get(name) {
+                *name = to.clone();
+            }
+        };
+        match self {
+            Root::Leaf(def) => def.content.rename(renames),
+            Root::Enum(def) => def.base.iter_mut().for_each(rename),
+            Root::Element(def) => {
+                for attr in &mut def.attributes {
+                    attr.content.rename(renames);
+                }
+                match &mut def.content {
+                    Some(ElementContent::Leaf(_, def)) => def.content.rename(renames),
+                    Some(ElementContent::Leaves(leaves)) => {
+                        for leaf in leaves {
+                            leaf.definition.content.rename(renames);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            Root::Choice(def) => {
+                for leaf in &mut def.variants {
+                    leaf.definition.content.rename(renames);
+                }
+            }
+            Root::Union(def) => {
+                for (_, content) in &mut def.variants {
+                    content.rename(renames);
+                }
+            }
+            Root::List(def) => def.item.rename(renames),
+            Root::Derived(def) => {
+                rename(&mut def.base);
+                def.derived.iter_mut().for_each(rename);
+            }
+        }
+    }
+
     pub fn is_enum(&self) -> bool {
-        matches!(self, Root::Enum(_) | Root::Choice(_))
+        matches!(
+            self,
+            Root::Enum(_) | Root::Choice(_) | Root::Union(_) | Root::Derived(_)
+        )
     }
 
     pub fn docs(&self) -> Option<&str> {
@@ -46,30 +152,91 @@ impl Root {
             Root::Enum(_) => None,
             Root::Element(def) => def.docs.as_deref(),
             Root::Choice(def) => def.docs.as_deref(),
+            Root::Union(def) => def.docs.as_deref(),
+            Root::List(def) => def.docs.as_deref(),
+            Root::Derived(_) => None,
         }
     }
 
     pub fn to_declaration(&self, root_name: &Ident, ctx: &SchemaContext) -> TokenStream {
         match self {
             Root::Leaf(def) => {
+                let def = def.flatten(ctx);
                 let inner = def.to_impl(ctx);
-                let mut tn = quote! {
-                    (pub #inner);
+                let has_restrictions = !def.restrictions.is_empty();
+                let mut tn = if has_restrictions {
+                    // keep the value private to only allow constructing valid values
+                    quote! {
+                        (#inner);
+                    }
+                } else {
+                    quote! {
+                        (pub #inner);
+                    }
                 };
-                if matches!(def.content, LeafContent::Literal(_)) {
-                    let type_ = root_name.to_string();
+                let validate = def.validate_impl(ctx);
+                if has_restrictions {
+                    tn.append_all(quote! {
+                        impl #root_name {
+                            pub fn new(val: #inner) -> Result<Self, ::xsd::restriction::RestrictionError> {
+                                {
+                                    let val = &val;
+                                    #validate
+                                }
+                                Ok(#root_name(val))
+                            }
+
+                            pub fn into_inner(self) -> #inner {
+                                self.0
+                            }
+                        }
+
+                        impl ::std::convert::TryFrom<#inner> for #root_name {
+                            type Error = ::xsd::restriction::RestrictionError;
+
+                            fn try_from(val: #inner) -> Result<Self, Self::Error> {
+                                Self::new(val)
+                            }
+                        }
+
+                        impl ::std::convert::AsRef<#inner> for #root_name {
+                            fn as_ref(&self) -> &#inner {
+                                &self.0
+                            }
+                        }
+                    })
+                }
+                // restrictions of named types (e.g. lists) are used as attributes or list items, too
+                let parse = match def.content {
+                    LeafContent::Literal(_) => {
+                        let type_ = root_name.to_string();
+                        Some(quote! {
+                            ::std::str::FromStr::from_str(s).map_err(|err| {
+                                ::xsd::decode::FromXmlError::ParseType {
+                                    type_: #type_.to_string(),
+                                    value: s.to_string(),
+                                    err: Box::new(err),
+                                }
+                            })?
+                        })
+                    }
+                    LeafContent::Named(_) if has_restrictions => {
+                        Some(quote!(::std::str::FromStr::from_str(s)?))
+                    }
+                    _ => None,
+                };
+                if let Some(parse) = parse {
                     tn.append_all(quote! {
                         impl ::std::str::FromStr for #root_name {
                             type Err = ::xsd::decode::FromXmlError;
 
                             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                                Ok(#root_name(::std::str::FromStr::from_str(s).map_err(|err| {
-                                    ::xsd::decode::FromXmlError::ParseType {
-                                        type_: #type_.to_string(),
-                                        value: s.to_string(),
-                                        err: Box::new(err),
-                                    }
-                                })?))
+                                let val: #inner = #parse;
+                                {
+                                    let val = &val;
+                                    #validate
+                                }
+                                Ok(#root_name(val))
                             }
                         }
 
@@ -82,8 +249,8 @@ impl Root {
                 }
                 tn
             }
-            Root::Enum(names) => {
-                let names = escape_enum_names(names.clone());
+            Root::Enum(EnumDefinition { variants, base }) => {
+                let names = escape_enum_names(variants.clone());
                 let variants = names.keys().map(|k| format_ident!("{}", k));
                 let from_str_variants = names.iter().map(|(variant, name)| {
                     let name_xml = &name.name;
@@ -100,6 +267,13 @@ impl Root {
                     }
                 });
 
+                let conversions = match base.as_ref().map(|base| (base, ctx.elements.get(base))) {
+                    Some((base, Some(Root::Enum(def)))) => {
+                        subset_conversions_impl(root_name, &names, &ctx.type_path(base), def)
+                    }
+                    _ => TokenStream::new(),
+                };
+
                 quote! {
                     {
                         #(#variants,)*
@@ -131,6 +305,8 @@ impl Root {
                             f.write_str(self.as_str())
                         }
                     }
+
+                    #conversions
                 }
             }
             Root::Element(def) => {
@@ -141,18 +317,166 @@ impl Root {
                     }
                 }
             }
-            Root::Choice(ChoiceDefinition { variants, .. }) => {
-                // TODO: use escape_enum_names?
-                let variants = variants.iter().map(|variant| {
-                    let variant_name = format_ident!("{}", variant.name.name.to_pascal_case());
-                    let mut type_ident = variant.definition.to_impl(ctx);
-                    if variant.is_vec() {
-                        type_ident = quote! { Vec<#type_ident> }
+            Root::List(def) => {
+                let item = def.item.to_impl(ctx);
+                let item_from_str = def.item.from_str_impl(ctx);
+                let inner = quote!(Vec<#item>);
+                let validate = def.validate_impl();
+                let mut tn = if def.restrictions.is_empty() {
+                    quote! {
+                        (pub #inner);
                     }
+                } else {
+                    // keep the items private to only allow constructing valid lists
                     quote! {
-                        #variant_name(#type_ident)
+                        (#inner);
+
+                        impl #root_name {
+                            pub fn new(val: #inner) -> Result<Self, ::xsd::restriction::RestrictionError> {
+                                {
+                                    let val = &val;
+                                    #validate
+                                }
+                                Ok(#root_name(val))
+                            }
+
+                            pub fn into_inner(self) -> #inner {
+                                self.0
+                            }
+                        }
+
+                        impl ::std::convert::TryFrom<#inner> for #root_name {
+                            type Error = ::xsd::restriction::RestrictionError;
+
+                            fn try_from(val: #inner) -> Result<Self, Self::Error> {
+                                Self::new(val)
+                            }
+                        }
+
+                        impl ::std::convert::AsRef<#inner> for #root_name {
+                            fn as_ref(&self) -> &#inner {
+                                &self.0
+                            }
+                        }
+                    }
+                };
+                tn.append_all(quote! {
+                    impl ::std::str::FromStr for #root_name {
+                        type Err = ::xsd::decode::FromXmlError;
+
+                        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                            let mut items: #inner = Vec::new();
+                            // items are separated by XML whitespace
+                            for val in s.split([' ', '\t', '\r', '\n']).filter(|val| !val.is_empty()) {
+                                items.push(#item_from_str);
+                            }
+                            {
+                                let val = &items;
+                                #validate
+                            }
+                            Ok(#root_name(items))
+                        }
+                    }
+
+                    impl ::std::fmt::Display for #root_name {
+                        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                            for (i, item) in self.0.iter().enumerate() {
+                                if i > 0 {
+                                    f.write_str(" ")?;
+                                }
+                                item.fmt(f)?;
+                            }
+                            Ok(())
+                        }
                     }
                 });
+                tn
+            }
+            Root::Union(UnionDefinition { variants, .. }) => {
+                let names = variants
+                    .iter()
+                    .map(|(name, _)| format_ident!("{}", name.name.to_pascal_case()))
+                    .collect::<Vec<_>>();
+                let parsers = variants
+                    .iter()
+                    .map(|(name, _)| format_ident!("parse_{}", name.name.to_snake_case()))
+                    .collect::<Vec<_>>();
+                let types = variants
+                    .iter()
+                    .map(|(_, content)| content.to_impl(ctx))
+                    .collect::<Vec<_>>();
+                let from_str = variants
+                    .iter()
+                    .map(|(_, content)| content.from_str_impl(ctx));
+
+                quote! {
+                    {
+                        #(#names(#types),)*
+                    }
+
+                    impl ::std::str::FromStr for #root_name {
+                        type Err = ::xsd::decode::FromXmlError;
+
+                        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                            // the members are tried in the order of their declaration
+                            #(
+                                if let Ok(val) = Self::#parsers(s) {
+                                    return Ok(Self::#names(val));
+                                }
+                            )*
+                            Err(::xsd::decode::FromXmlError::InvalidVariant {
+                                name: s.to_string(),
+                            })
+                        }
+                    }
+
+                    impl #root_name {
+                        #(
+                            fn #parsers(val: &str) -> Result<#types, ::xsd::decode::FromXmlError> {
+                                let val = #from_str;
+                                Ok(val)
+                            }
+                        )*
+                    }
+
+                    impl ::std::fmt::Display for #root_name {
+                        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                            match self {
+                                #(Self::#names(val) => val.fmt(f),)*
+                            }
+                        }
+                    }
+                }
+            }
+            Root::Derived(def) => {
+                let names = def.types().map(|name| name.to_impl());
+                let types = def.types().map(|name| ctx.type_path(name));
+                quote! {
+                    {
+                        #(#names(#types),)*
+                    }
+                }
+            }
+            Root::Choice(ChoiceDefinition {
+                variants, mixed, ..
+            }) => {
+                // TODO: use escape_enum_names?
+                let mut variants = variants
+                    .iter()
+                    .map(|variant| {
+                        let variant_name = format_ident!("{}", variant.name.name.to_pascal_case());
+                        let mut type_ident = variant.element_type_impl(ctx);
+                        if variant.is_vec() {
+                            type_ident = quote! { Vec<#type_ident> }
+                        }
+                        quote! {
+                            #variant_name(#type_ident)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if mixed.is_some() {
+                    variants.push(quote! { Text(String) });
+                }
 
                 quote! {
                     {
@@ -166,19 +490,14 @@ impl Root {
     pub fn to_xml_impl(&self, ctx: &SchemaContext) -> TokenStream {
         match self {
             Root::Leaf(def) => {
+                let def = def.flatten(ctx);
                 let inner = def.to_xml_impl(ctx);
                 let tn = quote! {
                     let val = &self.0;
                     #inner
                 };
                 // TODO: improve those cases to make them easier to understand
-                if !matches!(
-                    def,
-                    LeafDefinition {
-                        content: LeafContent::Named(_),
-                        ..
-                    }
-                ) {
+                if !matches!(def.content, LeafContent::Named(_)) {
                     quote! {
                         ctx.write_start_element(writer)?;
                         #tn
@@ -188,7 +507,7 @@ impl Root {
                     tn
                 }
             }
-            Root::Enum(_) => {
+            Root::Enum(_) | Root::Union(_) | Root::List(_) => {
                 quote! {
                     ctx.write_start_element(writer)?;
                     let val = self.to_string();
@@ -199,44 +518,89 @@ impl Root {
                 }
             }
             Root::Element(def) => def.to_xml_impl(ctx),
+            Root::Derived(def) => {
+                let base = def.base.to_impl();
+                let derived = def.derived.iter().map(|name| name.to_impl());
+                let types_xml = def.derived.iter().map(|name| ctx.get_xml_name(name));
+                // the type name is a QName, whose prefix must be in scope
+                let types_ns = def.derived.iter().map(|name| match name.namespace {
+                    Namespace::None => TokenStream::new(),
+                    Namespace::Id(id) => {
+                        let ns = ctx.namespaces.get_by_id(id);
+                        let prefix = &ns.prefix;
+                        let namespace = &ns.namespace;
+                        quote!(ctx.set_ns(#prefix, #namespace);)
+                    }
+                });
+                quote! {
+                    match self {
+                        Self::#base(val) => val.to_xml_writer(ctx, writer)?,
+                        #(Self::#derived(val) => {
+                            #types_ns
+                            ctx.set_xsi_type(#types_xml);
+                            val.to_xml_writer(ctx, writer)?;
+                        })*
+                    }
+                }
+            }
             Root::Choice(ChoiceDefinition {
                 variants,
                 is_virtual,
+                mixed,
                 ..
             }) => {
                 // TODO: use escape_enum_names?
-                let variants = variants.iter().map(|variant| {
-                    let variant_name = format_ident!("{}", variant.name.name.to_pascal_case());
-                    let name_xml = &variant.name.name;
-                    let inner = variant.definition.to_xml_impl(ctx);
-                    let is_literal = matches!(variant.definition.content, LeafContent::Literal(_));
-                    let mut inner = if is_literal {
-                        quote! {
-                            let mut ctx = ::xsd::Context::new(#name_xml);
-                            ctx.write_start_element(writer)?;
-                            #inner
-                            ctx.write_end_element(writer)?;
+                let mut variants = variants
+                    .iter()
+                    .map(|variant| {
+                        let variant_name = format_ident!("{}", variant.name.name.to_pascal_case());
+                        let name_xml = ctx.get_xml_name(&variant.name);
+                        let namespace_xml = ctx.quote_xml_namespace(&variant.name);
+                        let inner = variant.definition.to_xml_impl(ctx);
+                        let is_literal =
+                            matches!(variant.definition.content, LeafContent::Literal(_));
+                        let mut inner =
+                            if matches!(variant.definition.content, LeafContent::Wildcard(_)) {
+                                inner
+                            } else if is_literal {
+                                let body = variant.nil_to_xml_impl(quote! {
+                                    ctx.write_start_element(writer)?;
+                                    #inner
+                                    ctx.write_end_element(writer)?;
+                                });
+                                quote! {
+                                    let mut ctx = ctx.child(#name_xml, #namespace_xml);
+                                    #body
+                                }
+                            } else {
+                                let body = variant.nil_to_xml_impl(inner);
+                                quote! {
+                                    let mut ctx = ctx.child(#name_xml, #namespace_xml);
+                                    #body
+                                }
+                            };
+                        if variant.is_vec() {
+                            inner = quote! {
+                                for val in val {
+                                    #inner
+                                }
+                            };
                         }
-                    } else {
+
                         quote! {
-                            let mut ctx = ::xsd::Context::new(#name_xml);
-                            #inner
-                        }
-                    };
-                    if variant.is_vec() {
-                        inner = quote! {
-                            for val in val {
+                            Self::#variant_name(val) => {
                                 #inner
                             }
-                        };
-                    }
-
-                    quote! {
-                        Self::#variant_name(val) => {
-                            #inner
                         }
-                    }
-                });
+                    })
+                    .collect::<Vec<_>>();
+                if mixed.is_some() {
+                    variants.push(quote! {
+                        Self::Text(val) => {
+                            writer.write(XmlEvent::characters(val))?;
+                        }
+                    });
+                }
 
                 let tn = quote! {
                     match self {
@@ -260,12 +624,12 @@ impl Root {
     pub fn from_xml_impl(&self, name: &Ident, ctx: &SchemaContext) -> TokenStream {
         match self {
             Root::Leaf(def) => {
-                let inner = def.from_xml_impl(ctx);
+                let inner = def.flatten(ctx).from_xml_impl(ctx);
                 quote! {
                     #name(#inner)
                 }
             }
-            Root::Enum(_) => {
+            Root::Enum(_) | Root::Union(_) | Root::List(_) => {
                 quote! {
                     {
                         let val = node.text()?;
@@ -279,16 +643,47 @@ impl Root {
                     #name #inner
                 }
             }
-            Root::Choice(ChoiceDefinition { variants, .. }) => {
+            Root::Derived(def) => {
+                let variants = def.types().map(|name| name.to_impl());
+                let types = def.types().map(|name| ctx.type_path(name));
+                let names_xml = def.types().map(|name| &name.name);
+                let namespaces_xml = def.types().map(|name| ctx.quote_xml_namespace(name));
+                let base = def.base.to_impl();
+                let base_type = ctx.type_path(&def.base);
+                quote! {
+                    match node.xsi_type() {
+                        None => Self::#base(#base_type::from_xml_node(node)?),
+                        #(Some((#names_xml, #namespaces_xml)) => {
+                            Self::#variants(#types::from_xml_node(node)?)
+                        })*
+                        Some((name, _)) => {
+                            return Err(::xsd::decode::FromXmlError::UnknownType {
+                                name: name.to_string(),
+                            })
+                        }
+                    }
+                }
+            }
+            Root::Choice(ChoiceDefinition {
+                variants, mixed, ..
+            }) => {
+                let text = if mixed.is_some() {
+                    quote! {
+                        if let Some(val) = node.next_text() {
+                            Self::Text(val.to_string())
+                        } else
+                    }
+                } else {
+                    TokenStream::new()
+                };
+
                 // TODO: use escape_enum_names?
                 let variants = variants.iter().map(|variant| {
                     let variant_name = format_ident!("{}", variant.name.name.to_pascal_case());
-                    let inner = variant.definition.from_xml_impl(ctx);
-                    let name_xml = &variant.name.name;
-                    let namespace_xml = ctx.quote_xml_namespace(&variant.name);
+                    let inner = variant.nil_from_xml_impl(variant.definition.from_xml_impl(ctx));
                     if variant.is_virtual {
                         if let LeafContent::Named(name) = &variant.definition.content {
-                            let first_name = format_ident!("{}", name.name.to_pascal_case());
+                            let first_name = ctx.type_path(name);
                             quote! {
                                 if #first_name::lookahead(node) {
                                     Self::#variant_name(#inner)
@@ -301,11 +696,13 @@ impl Root {
                         }
                     } else {
                         #[allow(clippy::collapsible_else_if)]
+                        let next_child = variant.next_child_impl("next_child", ctx);
                         if variant.is_vec() {
+                            let peek_child = variant.peek_child_impl(ctx);
                             quote! {
-                                if node.peek_child(#name_xml, #namespace_xml) {
+                                if #peek_child {
                                     let mut vec = Vec::new();
-                                    while let Some(node) = node.next_child(#name_xml, #namespace_xml) {
+                                    while let Some(node) = #next_child {
                                         vec.push(#inner);
                                     }
                                     Self::#variant_name(vec)
@@ -313,7 +710,7 @@ impl Root {
                             }
                         } else {
                             quote! {
-                                if let Some(node) = node.next_child(#name_xml, #namespace_xml) {
+                                if let Some(node) = #next_child {
                                     Self::#variant_name(#inner)
                                 }
                             }
@@ -323,7 +720,7 @@ impl Root {
 
                 let variant_name = name.to_string();
                 quote! {
-                    #(#variants else )* {
+                    #text #(#variants else )* {
                         return Err(::xsd::decode::FromXmlError::MissingVariant {
                             name: #variant_name.to_string(),
                         }.into())
@@ -340,7 +737,7 @@ impl Root {
                     true
                 }
             }
-            Root::Enum(_) => {
+            Root::Enum(_) | Root::Union(_) | Root::List(_) | Root::Derived(_) => {
                 quote! {
                     true
                 }
@@ -365,16 +762,12 @@ impl Root {
                                 // TODO: reflect that in the type?
                                 unreachable!()
                             };
-                            let name = format_ident!("{}", name.name.to_pascal_case());
+                            let name = ctx.type_path(name);
                             Some(quote! {
                                 #name::lookahead(node)
                             })
                         } else {
-                            let name_xml = &leaf.name.name;
-                            let namespace_xml = ctx.quote_xml_namespace(&leaf.name);
-                            Some(quote! {
-                                node.peek_child(#name_xml, #namespace_xml)
-                            })
+                            Some(leaf.peek_child_impl(ctx))
                         }
                     });
 
@@ -387,28 +780,39 @@ impl Root {
                     }
                 }
             }
-            Root::Choice(ChoiceDefinition { variants, .. }) => {
-                let checks = variants.iter().map(|variant| {
-                    if variant.is_virtual {
-                        let name = if let LeafContent::Named(name) = &variant.definition.content {
-                            name
+            Root::Choice(ChoiceDefinition {
+                variants, mixed, ..
+            }) => {
+                let mut checks = variants
+                    .iter()
+                    .map(|variant| {
+                        if variant.is_virtual {
+                            let name = if let LeafContent::Named(name) = &variant.definition.content
+                            {
+                                name
+                            } else {
+                                // unreachable  ...
+                                // TODO: reflect that in the type?
+                                unreachable!()
+                            };
+                            let name = ctx.type_path(name);
+                            quote! {
+                                #name::lookahead(node)
+                            }
                         } else {
-                            // unreachable  ...
-                            // TODO: reflect that in the type?
-                            unreachable!()
-                        };
-                        let name = format_ident!("{}", name.name.to_pascal_case());
-                        quote! {
-                            #name::lookahead(node)
+                            variant.peek_child_impl(ctx)
                         }
-                    } else {
-                        let name_xml = &variant.name.name;
-                        let namespace_xml = ctx.quote_xml_namespace(&variant.name);
+                    })
+                    .collect::<Vec<_>>();
+                if mixed.is_some() {
+                    // must be checked first, as checking for elements skips text nodes
+                    checks.insert(
+                        0,
                         quote! {
-                            node.peek_child(#name_xml, #namespace_xml)
-                        }
-                    }
-                });
+                            node.peek_text()
+                        },
+                    );
+                }
 
                 quote! {
                     false #(|| #checks)*
@@ -418,6 +822,157 @@ impl Root {
     }
 }
 
+impl ListDefinition {
+    /// Checks the items `val` (a reference to the `Vec`) against the length facets, which count
+    /// the items, and the patterns, which match the whole list (the items separated by spaces).
+    fn validate_impl(&self) -> TokenStream {
+        let checks = self.restrictions.iter().map(|restriction| {
+            let (op, n) = match restriction {
+                Restriction::Length(n) => (quote!(!=), n),
+                Restriction::MinLength(n) => (quote!(<), n),
+                Restriction::MaxLength(n) => (quote!(>), n),
+                Restriction::Pattern(_) => {
+                    let check = restriction.validate_impl(None);
+                    return quote! {
+                        {
+                            let val = val.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(" ");
+                            #check
+                        }
+                    };
+                }
+                _ => return TokenStream::new(),
+            };
+            let facet = restriction.facet();
+            let value = restriction.value();
+            quote! {
+                if val.len() #op #n {
+                    return Err(::xsd::restriction::RestrictionError {
+                        facet: #facet,
+                        restriction: #value,
+                        value: val.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(" "),
+                    }.into());
+                }
+            }
+        });
+        quote! {
+            #(#checks)*
+        }
+    }
+}
+
+impl ChoiceDefinition {
+    /// Checks the decoded mixed content `vec` (a `Vec` of the choice `type_path`) against the
+    /// occurrences of the child elements declared by the model group.
+    pub fn check_occurrences_impl(
+        &self,
+        type_path: &TokenStream,
+        ctx: &SchemaContext,
+    ) -> TokenStream {
+        let Some(mixed) = self.mixed.as_ref().filter(|mixed| !mixed.occurs.is_empty()) else {
+            return TokenStream::new();
+        };
+
+        let max = |max: &MaxOccurs| match max {
+            MaxOccurs::Number(n) => quote!(Some(#n)),
+            MaxOccurs::Unbounded => quote!(None),
+        };
+        let MinOccurs(min) = mixed.min_occurs;
+        let group_max = max(&mixed.max_occurs);
+        let particle = match mixed.group {
+            ModelGroup::Sequence => {
+                quote!(::xsd::mixed::Particle::Sequence { min: #min, max: #group_max })
+            }
+            ModelGroup::All => quote!(::xsd::mixed::Particle::All { min: #min }),
+            ModelGroup::Choice => {
+                quote!(::xsd::mixed::Particle::Choice { min: #min, max: #group_max })
+            }
+        };
+        let elements = self.variants.iter().zip(&mixed.occurs).map(
+            |(variant, (MinOccurs(min), max_occurs))| {
+                let name_xml = &variant.name.name;
+                let namespace_xml = ctx.quote_xml_namespace(&variant.name);
+                let max = max(max_occurs);
+                quote! {
+                    ::xsd::mixed::Occurs {
+                        name: #name_xml,
+                        namespace: #namespace_xml,
+                        min: #min,
+                        max: #max,
+                    }
+                }
+            },
+        );
+        let indices = self.variants.iter().enumerate().map(|(i, variant)| {
+            let variant_name = format_ident!("{}", variant.name.name.to_pascal_case());
+            quote!(#type_path::#variant_name(_) => Some(#i))
+        });
+
+        quote! {
+            ::xsd::mixed::check_occurrences(
+                vec.iter().filter_map(|item| match item {
+                    #(#indices,)*
+                    #type_path::Text(_) => None,
+                }),
+                #particle,
+                &[#(#elements),*],
+            )?;
+        }
+    }
+}
+
+impl DerivedDefinition {
+    /// The base type followed by all derived types.
+    pub fn types(&self) -> impl Iterator<Item = &Name> {
+        std::iter::once(&self.base).chain(&self.derived)
+    }
+}
+
+/// Conversions between a subset enum and the enum it restricts.
+fn subset_conversions_impl(
+    root_name: &Ident,
+    names: &HashMap<String, Name>,
+    base_name: &TokenStream,
+    base: &EnumDefinition,
+) -> TokenStream {
+    let base_names = escape_enum_names(base.variants.clone());
+    let into_base = names
+        .iter()
+        .map(|(variant, name)| {
+            let (base_variant, _) = base_names.iter().find(|(_, base)| base.name == name.name)?;
+            let variant = format_ident!("{}", variant);
+            let base_variant = format_ident!("{}", base_variant);
+            Some(quote! {
+                #root_name::#variant => #base_name::#base_variant
+            })
+        })
+        .collect::<Option<Vec<_>>>();
+
+    // a subset with values that are not part of the base (an invalid schema) can only be
+    // converted from the base
+    let mut tn = TokenStream::new();
+    if let Some(into_base) = into_base {
+        tn.append_all(quote! {
+            impl ::std::convert::From<#root_name> for #base_name {
+                fn from(val: #root_name) -> Self {
+                    match val {
+                        #(#into_base,)*
+                    }
+                }
+            }
+        });
+    }
+    tn.append_all(quote! {
+        impl ::std::convert::TryFrom<#base_name> for #root_name {
+            type Error = ::xsd::decode::FromXmlError;
+
+            fn try_from(val: #base_name) -> Result<Self, Self::Error> {
+                ::std::str::FromStr::from_str(val.as_str())
+            }
+        }
+    });
+    tn
+}
+
 fn escape_enum_names(names: Vec<Name>) -> HashMap<String, Name> {
     let mut unknown_count = 0;
     let mut enum_names = HashMap::with_capacity(names.len());
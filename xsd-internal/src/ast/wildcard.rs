@@ -0,0 +1,38 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The namespace constraint of an `<xs:any>` wildcard (namespaces are given by their URI, `None`
+/// meaning unqualified elements).
+#[derive(Debug, Clone)]
+pub enum Wildcard {
+    /// `##any`
+    Any,
+    /// `##other`: qualified elements of any namespace but the target namespace.
+    Other(Option<String>),
+    /// A list of namespaces, including `##targetNamespace` and `##local`.
+    List(Vec<Option<String>>),
+}
+
+impl Wildcard {
+    /// A closure checking whether an element with the given namespace is matched by the wildcard.
+    pub fn to_matcher_impl(&self) -> TokenStream {
+        match self {
+            Wildcard::Any => quote!(|_| true),
+            Wildcard::Other(target) => {
+                let target = quote_namespace(target.as_deref());
+                quote!(|ns: Option<&str>| ns.is_some() && ns != #target)
+            }
+            Wildcard::List(namespaces) => {
+                let namespaces = namespaces.iter().map(|ns| quote_namespace(ns.as_deref()));
+                quote!(|ns: Option<&str>| false #(|| ns == #namespaces)*)
+            }
+        }
+    }
+}
+
+fn quote_namespace(namespace: Option<&str>) -> TokenStream {
+    match namespace {
+        Some(ns) => quote!(Some(#ns)),
+        None => quote!(None),
+    }
+}
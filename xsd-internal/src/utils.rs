@@ -11,3 +11,45 @@ pub fn escape_ident(name: &str) -> syn::Ident {
         _ => format_ident!("{}", name),
     }
 }
+
+/// Translates an XSD `pattern` facet into an equivalent (anchored) pattern for the `regex` crate.
+/// XSD patterns always match the whole value, do not know `^` and `$` anchors, and support the
+/// additional `\i` and `\c` (XML name char) escapes.
+pub fn translate_pattern(pattern: &str) -> String {
+    let mut regex = String::with_capacity(pattern.len() + 6);
+    regex.push_str("^(?:");
+
+    let mut chars = pattern.chars();
+    let mut class_depth = 0usize;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('i') => regex.push_str(r"[\p{L}_:]"),
+                Some('I') => regex.push_str(r"[^\p{L}_:]"),
+                Some('c') => regex.push_str(r"[\p{L}\p{N}._:\-]"),
+                Some('C') => regex.push_str(r"[^\p{L}\p{N}._:\-]"),
+                Some(c) => {
+                    regex.push('\\');
+                    regex.push(c);
+                }
+                None => regex.push('\\'),
+            },
+            '[' => {
+                class_depth += 1;
+                regex.push(c);
+            }
+            ']' => {
+                class_depth = class_depth.saturating_sub(1);
+                regex.push(c);
+            }
+            '^' | '$' if class_depth == 0 => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+
+    regex.push_str(")$");
+    regex
+}
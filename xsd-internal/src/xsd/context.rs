@@ -1,9 +1,17 @@
 use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
 
 use super::error::XsdError;
 use super::node::Attribute;
-use super::schema::Schema;
-use crate::ast::{LeafContent, LeafDefinition, LiteralType, Name, Namespace, Namespaces, Root};
+use super::schema::{ParseError, Schema, SchemaError};
+use crate::ast::{
+    AttributeGroup, ChoiceDefinition, ElementContent, ElementDefinition, IdentityConstraint,
+    IdentityKind, Leaf, LeafContent, LeafDefinition, LiteralType, MaxOccurs, MinOccurs, Name,
+    Namespace, Namespaces, Restriction, Root,
+};
+use crate::utils::escape_ident;
+use inflector::Inflector;
 use proc_macro2::TokenStream;
 use quote::quote;
 
@@ -15,6 +23,12 @@ pub struct Context<'input> {
     target_namespace: Namespace,
     document_namespaces: HashMap<&'input str, &'input str>,
     is_qualified: bool,
+    /// Whether local attributes are in the target namespace (`attributeFormDefault`).
+    is_attribute_qualified: bool,
+    /// Whether the schema has no target namespace of its own, but adopted the one of the schema
+    /// including it (chameleon include).
+    is_chameleon: bool,
+    identity_constraints: HashMap<Name, Vec<IdentityConstraint>>,
     shared: SharedContext,
 }
 
@@ -24,6 +38,54 @@ pub struct SharedContext {
     pub namespaces: Namespaces,
     /// Dependencies between structs. Key = parent, Value = child
     pub dependencies: HashMap<Name, HashSet<Name>>,
+    pub pending: PendingReferences,
+    pub files: SchemaFiles,
+}
+
+/// The schema files loaded via includes/imports, to load each file only once.
+#[derive(Debug, Default)]
+pub struct SchemaFiles {
+    /// All files loaded so far by their canonical path and the namespace they were loaded into
+    /// (which differs for chameleon includes).
+    pub loaded: HashSet<(PathBuf, Namespace)>,
+    /// The files currently being loaded, from the outermost to the innermost one.
+    pub chain: Vec<PathBuf>,
+    /// The namespaces of imports without a `schemaLocation`, with the directory of the importing
+    /// schema.
+    pub namespace_imports: Vec<(String, PathBuf)>,
+}
+
+/// References that are only resolved once all schema files are parsed, as the referenced
+/// definitions can appear after (or in another file than) their usage.
+#[derive(Debug, Default)]
+pub struct PendingReferences {
+    /// Top-level attribute groups by name.
+    pub attribute_groups: HashMap<Name, AttributeGroup>,
+    /// Key = element the attributes are added to, Value = referenced group
+    pub attribute_group_refs: Vec<(Name, Name)>,
+    /// Complex types derived by `<xs:restriction>`, which inherit the attributes of their base.
+    pub restrictions: Vec<ComplexRestriction>,
+    /// Key = head of the substitution group, Value = member (in the order of their declaration)
+    pub substitution_groups: Vec<(Name, Name)>,
+    /// Elements declared with `abstract="true"`, which are not part of their substitution group.
+    pub abstract_elements: HashSet<Name>,
+    /// Key = base type, Value = named complex type derived from it
+    pub derived_types: Vec<(Name, Name)>,
+    /// Top-level elements declared with `nillable="true"`, which are nillable wherever they are
+    /// referenced.
+    pub nillable_elements: HashSet<Name>,
+    /// Key = root that contains the element declaring the constraints
+    pub identity_constraints: Vec<(Name, Vec<IdentityConstraint>)>,
+}
+
+#[derive(Debug)]
+pub struct ComplexRestriction {
+    pub element: Name,
+    pub base: Name,
+    /// Attributes of the base that are removed (`use="prohibited"`).
+    pub prohibited: Vec<Name>,
+    /// The facets of a `<xs:simpleContent>` restriction, which narrow the text of the base.
+    pub facets: Option<Vec<Restriction>>,
 }
 
 /// The context reduced to the data necessary for the code-generation.
@@ -31,8 +93,31 @@ pub struct SharedContext {
 pub struct SchemaContext {
     pub elements: HashMap<Name, Root>,
     pub target_namespace: Namespace,
-    pub is_qualified: bool,
     pub namespaces: Namespaces,
+    /// Key = root that contains the element declaring the constraints
+    pub identity_constraints: HashMap<Name, Vec<IdentityConstraint>>,
+    /// The name of the submodule the types of a namespace are generated in, by namespace id.
+    pub modules: HashMap<usize, String>,
+    pub options: Options,
+}
+
+/// Settings that change the generated code.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Generate non-optional fields for elements with a `default` value, which are filled with
+    /// the default if the element is empty (or absent, for optional elements).
+    pub element_defaults: bool,
+    /// Generate non-optional fields for attributes with a `default` value, which are filled with
+    /// the default if the attribute is missing.
+    pub attribute_defaults: bool,
+    /// Do not write attributes whose value equals their `default` value (requires
+    /// `attribute_defaults`).
+    pub omit_default_attributes: bool,
+    /// Replace usages of complex types that are extended by other types with an enum over the
+    /// base and derived types, which is selected by the `xsi:type` attribute.
+    pub xsi_type: bool,
+    /// Submodule names by namespace URI, overriding the ones derived from the URI.
+    pub namespace_modules: HashMap<String, String>,
 }
 
 pub const NS_XSD: &str = "http://www.w3.org/2001/XMLSchema";
@@ -49,18 +134,49 @@ impl<'input> Context<'input> {
                 document_namespaces.insert(prefix, ns.uri());
             }
         }
+        let target_namespace = target_namespace
+            .map(|tn| shared.namespaces.get_or_insert(tn))
+            .unwrap_or_default();
+        let is_qualified = schema.attribute("elementFormDefault") == Some("qualified");
+        if is_qualified {
+            shared.namespaces.set_qualified(target_namespace);
+        }
         Context {
             roots: Default::default(),
             default_namespace: schema.default_namespace(),
-            target_namespace: target_namespace
-                .map(|tn| shared.namespaces.get_or_insert(tn))
-                .unwrap_or_default(),
+            target_namespace,
             document_namespaces,
-            is_qualified: schema.attribute("elementFormDefault") == Some("qualified"),
+            is_qualified,
+            is_attribute_qualified: schema.attribute("attributeFormDefault") == Some("qualified"),
+            is_chameleon: false,
+            identity_constraints: Default::default(),
             shared,
         }
     }
 
+    /// Adopts the target namespace of the including schema for a schema without one. Unqualified
+    /// references then refer to components in that namespace, too.
+    pub fn adopt_namespace(&mut self, namespace: Namespace) {
+        if namespace != Namespace::None {
+            self.target_namespace = namespace;
+            self.is_chameleon = true;
+        }
+    }
+
+    pub fn files(&mut self) -> &mut SchemaFiles {
+        &mut self.shared.files
+    }
+
+    /// Whether a schema with the given target namespace was loaded already.
+    pub fn is_namespace_loaded(&mut self, namespace: &str) -> bool {
+        let namespace = self.shared.namespaces.get_or_insert(namespace);
+        self.shared
+            .files
+            .loaded
+            .iter()
+            .any(|(_, loaded)| *loaded == namespace)
+    }
+
     pub fn take_shared(&mut self) -> SharedContext {
         std::mem::take(&mut self.shared)
     }
@@ -72,15 +188,748 @@ impl<'input> Context<'input> {
         self.roots.insert(name, root);
     }
 
+    /// Replaces the provisional names of the top-level groups and the types nested in them (see
+    /// [crate::xsd::parse::group::group_name]) with names that are not taken by other types.
+    pub fn resolve_group_names(&mut self) {
+        let is_group = |name: &Name| name.name.contains('#');
+        let mut groups = self
+            .roots
+            .keys()
+            .filter(|name| is_group(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut taken = self
+            .roots
+            .keys()
+            .filter(|name| !is_group(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        let renames = groups
+            .into_iter()
+            .map(|from| {
+                let name = from.name.replace('#', "");
+                let mut to = Name::new(name.clone(), from.namespace);
+                let mut n = 1;
+                while taken.iter().any(|other| {
+                    other.namespace == to.namespace
+                        && other.name.to_pascal_case() == to.name.to_pascal_case()
+                }) {
+                    n += 1;
+                    to = Name::new(format!("{}{}", name, n), from.namespace);
+                }
+                taken.push(to.clone());
+                (from, to)
+            })
+            .collect::<HashMap<_, _>>();
+
+        self.rename_definitions(&renames);
+        rename_references(
+            &mut self.roots,
+            &mut self.shared.dependencies,
+            &mut self.shared.pending,
+            &renames,
+        );
+    }
+
+    pub fn add_attribute_group(&mut self, name: Name, group: AttributeGroup) {
+        self.shared.pending.attribute_groups.insert(name, group);
+    }
+
+    pub fn add_attribute_group_ref(&mut self, element: &Name, group: Name) {
+        self.shared
+            .pending
+            .attribute_group_refs
+            .push((element.clone(), group));
+    }
+
+    /// Adds the attributes of all referenced attribute groups (including nested groups) to the
+    /// referencing elements.
+    pub fn resolve_attribute_groups(&mut self) -> Result<(), SchemaError> {
+        for (element, group) in std::mem::take(&mut self.shared.pending.attribute_group_refs) {
+            let mut resolved = AttributeGroup::default();
+            let mut pending = vec![group];
+            let mut visited = HashSet::new();
+            while let Some(name) = pending.pop() {
+                if !visited.insert(name.clone()) {
+                    continue;
+                }
+                let group = self
+                    .shared
+                    .pending
+                    .attribute_groups
+                    .get(&name)
+                    .ok_or_else(|| SchemaError::NotFound {
+                        name: name.name.clone(),
+                    })?;
+                resolved.attributes.extend(group.attributes.iter().cloned());
+                if resolved.any_attribute.is_none() {
+                    resolved.any_attribute.clone_from(&group.any_attribute);
+                }
+                pending.extend(group.groups.iter().rev().cloned());
+
+                // types used by the group's attributes are now used by the element
+                if let Some(dependencies) = self.shared.dependencies.get(&name).cloned() {
+                    self.shared
+                        .dependencies
+                        .entry(element.clone())
+                        .or_default()
+                        .extend(dependencies);
+                }
+            }
+
+            match self.roots.get_mut(&element) {
+                Some(Root::Element(def)) => {
+                    def.attributes.extend(resolved.attributes);
+                    if def.any_attribute.is_none() {
+                        def.any_attribute = resolved.any_attribute;
+                    }
+                }
+                _ => {
+                    return Err(SchemaError::NotFound {
+                        name: element.name.clone(),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the top-level component `name` of a previously loaded schema by the component
+    /// parsed by `parse`. For `<xs:redefine>` (`keep_original`), the original is kept as
+    /// `{name}Base`, which is what references of the new component to `name` itself refer to. For
+    /// `<xs:override>`, the original is dropped.
+    pub fn replace_component(
+        &mut self,
+        name: &Name,
+        keep_original: bool,
+        parse: impl FnOnce(&mut Self) -> Result<(), ParseError>,
+    ) -> Result<(), ParseError> {
+        // parse the new component in isolation to tell its definitions apart from the originals
+        let roots = std::mem::take(&mut self.roots);
+        let dependencies = std::mem::take(&mut self.shared.dependencies);
+        let pending = std::mem::take(&mut self.shared.pending);
+        parse(self)?;
+        let mut new_roots = std::mem::replace(&mut self.roots, roots);
+        let mut new_dependencies = std::mem::replace(&mut self.shared.dependencies, dependencies);
+        let mut new_pending = std::mem::replace(&mut self.shared.pending, pending);
+
+        // the replaced component and the virtual types named after it
+        let replaced = new_roots
+            .keys()
+            .chain(new_pending.attribute_groups.keys())
+            .filter(|name| {
+                self.roots.contains_key(name)
+                    || self.shared.pending.attribute_groups.contains_key(name)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if !replaced.contains(name) {
+            // overrides of components that do not exist are ignored
+            return if keep_original {
+                Err(SchemaError::NotFound {
+                    name: name.name.clone(),
+                }
+                .into())
+            } else {
+                Ok(())
+            };
+        }
+
+        if keep_original {
+            // the originals must not replace other components (e.g. an existing `AddressBase`)
+            let mut taken = self
+                .roots
+                .keys()
+                .chain(self.shared.pending.attribute_groups.keys())
+                .chain(new_roots.keys())
+                .chain(new_pending.attribute_groups.keys())
+                .cloned()
+                .collect::<Vec<_>>();
+            let renames = replaced
+                .into_iter()
+                .map(|from| {
+                    let mut to = Name::new(format!("{}Base", from.name), from.namespace);
+                    let mut n = 1;
+                    while taken.iter().any(|other| {
+                        other.namespace == to.namespace
+                            && other.name.to_pascal_case() == to.name.to_pascal_case()
+                    }) {
+                        n += 1;
+                        to = Name::new(format!("{}Base{}", from.name, n), from.namespace);
+                    }
+                    taken.push(to.clone());
+                    (from, to)
+                })
+                .collect::<HashMap<_, _>>();
+            self.rename_definitions(&renames);
+
+            // everything else keeps referencing `name`, which is now the new component
+            let mut references = renames.clone();
+            let base = references.remove(name);
+            rename_references(
+                &mut self.roots,
+                &mut self.shared.dependencies,
+                &mut self.shared.pending,
+                &references,
+            );
+
+            // while the new component refers to the original
+            new_pending
+                .derived_types
+                .retain(|(base, derived)| base != name || derived != name);
+            if let Some(base) = base {
+                rename_references(
+                    &mut new_roots,
+                    &mut new_dependencies,
+                    &mut new_pending,
+                    &HashMap::from([(name.clone(), base)]),
+                );
+            }
+            self.merge(new_roots, new_dependencies, new_pending);
+        } else {
+            for name in &replaced {
+                self.roots.remove(name);
+                self.shared.dependencies.remove(name);
+                self.shared.pending.attribute_groups.remove(name);
+            }
+            let pending = &mut self.shared.pending;
+            pending
+                .attribute_group_refs
+                .retain(|(element, _)| !replaced.contains(element));
+            pending
+                .restrictions
+                .retain(|restriction| !replaced.contains(&restriction.element));
+            pending
+                .derived_types
+                .retain(|(_, derived)| !replaced.contains(derived));
+            pending
+                .substitution_groups
+                .retain(|(_, member)| !replaced.contains(member));
+            pending
+                .abstract_elements
+                .retain(|element| !replaced.contains(element));
+            pending
+                .nillable_elements
+                .retain(|element| !replaced.contains(element));
+            pending
+                .identity_constraints
+                .retain(|(root, _)| !replaced.contains(root));
+            self.merge(new_roots, new_dependencies, new_pending);
+        }
+
+        Ok(())
+    }
+
+    /// Moves the definitions (and the data pending for them) named after the keys of `renames`
+    /// to the corresponding values.
+    fn rename_definitions(&mut self, renames: &HashMap<Name, Name>) {
+        let rename = |name: &mut Name| {
+            if let Some(to) = renames.get(name) {
+                *name = to.clone();
+            }
+        };
+        for (from, to) in renames {
+            if let Some(root) = self.roots.remove(from) {
+                self.roots.insert(to.clone(), root);
+            }
+            if let Some(dependencies) = self.shared.dependencies.remove(from) {
+                self.shared.dependencies.insert(to.clone(), dependencies);
+            }
+            if let Some(group) = self.shared.pending.attribute_groups.remove(from) {
+                self.shared
+                    .pending
+                    .attribute_groups
+                    .insert(to.clone(), group);
+            }
+        }
+
+        let pending = &mut self.shared.pending;
+        for (element, _) in &mut pending.attribute_group_refs {
+            rename(element);
+        }
+        for restriction in &mut pending.restrictions {
+            rename(&mut restriction.element);
+        }
+        for (_, derived) in &mut pending.derived_types {
+            rename(derived);
+        }
+        for (root, _) in &mut pending.identity_constraints {
+            rename(root);
+        }
+    }
+
+    /// Adds the definitions of a component parsed by [Context::replace_component].
+    fn merge(
+        &mut self,
+        roots: HashMap<Name, Root>,
+        dependencies: HashMap<Name, HashSet<Name>>,
+        pending: PendingReferences,
+    ) {
+        self.roots.extend(roots);
+        for (parent, dependencies) in dependencies {
+            self.shared
+                .dependencies
+                .entry(parent)
+                .or_default()
+                .extend(dependencies);
+        }
+
+        let PendingReferences {
+            attribute_groups,
+            attribute_group_refs,
+            restrictions,
+            substitution_groups,
+            abstract_elements,
+            derived_types,
+            nillable_elements,
+            identity_constraints,
+        } = pending;
+        let shared = &mut self.shared.pending;
+        shared.attribute_groups.extend(attribute_groups);
+        shared.attribute_group_refs.extend(attribute_group_refs);
+        shared.restrictions.extend(restrictions);
+        shared.substitution_groups.extend(substitution_groups);
+        shared.abstract_elements.extend(abstract_elements);
+        shared.derived_types.extend(derived_types);
+        shared.nillable_elements.extend(nillable_elements);
+        shared.identity_constraints.extend(identity_constraints);
+    }
+
+    pub fn add_substitution_group_member(&mut self, head: Name, member: &Name) {
+        self.shared
+            .pending
+            .substitution_groups
+            .push((head, member.clone()));
+    }
+
+    pub fn add_abstract_element(&mut self, element: &Name) {
+        self.shared
+            .pending
+            .abstract_elements
+            .insert(element.clone());
+    }
+
+    pub fn add_nillable_element(&mut self, element: &Name) {
+        self.shared
+            .pending
+            .nillable_elements
+            .insert(element.clone());
+    }
+
+    pub fn add_identity_constraints(&mut self, root: &Name, constraints: Vec<IdentityConstraint>) {
+        if !constraints.is_empty() {
+            self.shared
+                .pending
+                .identity_constraints
+                .push((root.clone(), constraints));
+        }
+    }
+
+    /// Makes sure that all keyrefs refer to an existing key or unique constraint.
+    pub fn resolve_identity_constraints(&mut self) -> Result<(), SchemaError> {
+        let pending = std::mem::take(&mut self.shared.pending.identity_constraints);
+        let keys = pending
+            .iter()
+            .flat_map(|(_, constraints)| constraints)
+            .filter(|constraint| !matches!(constraint.kind, IdentityKind::KeyRef { .. }))
+            .map(|constraint| &constraint.name)
+            .collect::<HashSet<_>>();
+        for (_, constraints) in &pending {
+            for constraint in constraints {
+                if let IdentityKind::KeyRef { refer } = &constraint.kind {
+                    if !keys.contains(refer) {
+                        return Err(SchemaError::NotFound {
+                            name: refer.name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for (root, constraints) in pending {
+            self.identity_constraints
+                .entry(root)
+                .or_default()
+                .extend(constraints);
+        }
+
+        Ok(())
+    }
+
+    /// Marks all references (`<xs:element ref="" />`) of nillable top-level elements as nillable.
+    pub fn resolve_nillable_elements(&mut self) {
+        let nillable = std::mem::take(&mut self.shared.pending.nillable_elements);
+        for root in self.roots.values_mut() {
+            let leaves = match root {
+                Root::Element(ElementDefinition {
+                    content: Some(ElementContent::Leaves(leaves)),
+                    ..
+                }) => leaves,
+                Root::Choice(def) => &mut def.variants,
+                _ => continue,
+            };
+            for leaf in leaves.iter_mut().filter(|leaf| !leaf.is_virtual) {
+                if matches!(&leaf.definition.content, LeafContent::Named(name) if nillable.contains(name))
+                {
+                    leaf.is_nillable = true;
+                }
+            }
+        }
+    }
+
+    /// Replaces all references of substitution group heads by a virtual enum over the concrete
+    /// elements that can be used in place of the head (including members of members).
+    pub fn resolve_substitution_groups(&mut self) -> Result<(), SchemaError> {
+        let groups = std::mem::take(&mut self.shared.pending.substitution_groups);
+        let abstract_elements = std::mem::take(&mut self.shared.pending.abstract_elements);
+
+        let mut heads: Vec<Name> = Vec::new();
+        for (head, _) in &groups {
+            if !heads.contains(head) {
+                heads.push(head.clone());
+            }
+        }
+
+        let mut substitutions = HashMap::new();
+        for head in heads {
+            let mut variants = Vec::new();
+            let mut pending = vec![head.clone()];
+            let mut visited = HashSet::new();
+            while let Some(element) = pending.pop() {
+                if !visited.insert(element.clone()) {
+                    continue;
+                }
+                if !abstract_elements.contains(&element) {
+                    variants.push(self.substitution_variant(element.clone())?);
+                }
+                pending.extend(
+                    groups
+                        .iter()
+                        .rev()
+                        .filter(|(head, _)| *head == element)
+                        .map(|(_, member)| member.clone()),
+                );
+            }
+
+            // the name of the enum must not replace a type of the schema
+            let mut name = Name::new(format!("{}Substitution", head.name), head.namespace);
+            let mut n = 1;
+            while self.roots.keys().any(|other| {
+                other.namespace == name.namespace
+                    && other.name.to_pascal_case() == name.name.to_pascal_case()
+            }) {
+                n += 1;
+                name = Name::new(format!("{}Substitution{}", head.name, n), head.namespace);
+            }
+            for variant in &variants {
+                if let LeafContent::Named(dependency) = &variant.definition.content {
+                    self.discover_type(dependency, Some(&name));
+                }
+            }
+            self.roots.insert(
+                name.clone(),
+                Root::Choice(ChoiceDefinition {
+                    variants,
+                    is_virtual: true,
+                    mixed: None,
+                    docs: None,
+                }),
+            );
+            substitutions.insert(head, name);
+        }
+
+        // references of the heads become references of the enums (except for the variants of the
+        // enums themselves, which are the elements of the group)
+        let enums = substitutions.values().cloned().collect::<HashSet<_>>();
+        let mut dependencies = Vec::new();
+        for (parent, root) in &mut self.roots {
+            if enums.contains(parent) {
+                continue;
+            }
+            let leaves = match root {
+                Root::Element(ElementDefinition {
+                    content: Some(ElementContent::Leaves(leaves)),
+                    ..
+                }) => leaves,
+                Root::Choice(def) => &mut def.variants,
+                _ => continue,
+            };
+            for leaf in leaves.iter_mut().filter(|leaf| !leaf.is_virtual) {
+                if let LeafContent::Named(name) = &leaf.definition.content {
+                    if let Some(substitution) = substitutions.get(name) {
+                        leaf.definition.content = LeafContent::Named(substitution.clone());
+                        leaf.is_virtual = true;
+                        dependencies.push((parent.clone(), substitution.clone()));
+                    }
+                }
+            }
+        }
+        for (parent, substitution) in dependencies {
+            self.discover_type(&substitution, Some(&parent));
+        }
+
+        Ok(())
+    }
+
+    /// The variant of a substitution group enum for the given (top-level) element.
+    fn substitution_variant(&self, element: Name) -> Result<Leaf, SchemaError> {
+        let definition = match self.roots.get(&element) {
+            // inline the type of the element (`<xs:element name="" type="" />`)
+            Some(Root::Leaf(def)) => def.clone(),
+            Some(_) => LeafDefinition {
+                content: LeafContent::Named(element.clone()),
+                restrictions: Vec::new(),
+                docs: None,
+            },
+            None => {
+                return Err(SchemaError::NotFound {
+                    name: element.name.clone(),
+                })
+            }
+        };
+        Ok(Leaf {
+            name: element,
+            definition,
+            is_unordered: false,
+            is_virtual: false,
+            is_nillable: false,
+            min_occurs: MinOccurs::default(),
+            max_occurs: MaxOccurs::default(),
+            default: None,
+        })
+    }
+
+    pub fn add_derived_type(&mut self, base: Name, derived: &Name) {
+        self.shared
+            .pending
+            .derived_types
+            .push((base, derived.clone()));
+    }
+
+    /// Rejects simple types that are (indirectly) restrictions of themselves.
+    pub fn check_circular_types(&self) -> Result<(), SchemaError> {
+        for (name, root) in &self.roots {
+            let mut visited = HashSet::from([name]);
+            let mut next = root;
+            while let Root::Leaf(LeafDefinition {
+                content: LeafContent::Named(base),
+                ..
+            }) = next
+            {
+                if !visited.insert(base) {
+                    return Err(SchemaError::CircularType {
+                        name: base.name.clone(),
+                    });
+                }
+                match self.roots.get(base) {
+                    Some(root) => next = root,
+                    None => break,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Turns restrictions of named lists into lists themselves, as their facets apply to the list
+    /// (e.g. `length` counts the items instead of the characters).
+    pub fn resolve_list_restrictions(&mut self) -> Result<(), SchemaError> {
+        let mut lists = Vec::new();
+        for (name, root) in &self.roots {
+            let Root::Leaf(def) = root else {
+                continue;
+            };
+            if def.restrictions.is_empty() {
+                continue;
+            }
+
+            let mut restrictions = def.restrictions.clone();
+            let mut next = def;
+            while let LeafContent::Named(base) = &next.content {
+                match self.roots.get(base) {
+                    Some(Root::Leaf(base)) => {
+                        restrictions.splice(0..0, base.restrictions.iter().cloned());
+                        next = base;
+                    }
+                    Some(Root::List(list)) => {
+                        if let Some(restriction) = restrictions
+                            .iter()
+                            .find(|restriction| !restriction.applies_to_lists())
+                        {
+                            return Err(SchemaError::UnsupportedFacet {
+                                name: name.name.clone(),
+                                facet: restriction.facet(),
+                            });
+                        }
+                        let mut list = list.clone();
+                        list.restrictions.extend(restrictions);
+                        list.docs.clone_from(&def.docs);
+                        lists.push((name.clone(), list));
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        for (name, list) in lists {
+            if let LeafContent::Named(item) = &list.item {
+                let item = item.clone();
+                self.discover_type(&item, Some(&name));
+            }
+            self.roots.insert(name, Root::List(list));
+        }
+
+        Ok(())
+    }
+
+    pub fn add_complex_restriction(&mut self, restriction: ComplexRestriction) {
+        self.shared.pending.restrictions.push(restriction);
+    }
+
+    /// Adds the attributes of the base type to all types derived by restriction, unless they are
+    /// prohibited or overridden by the restriction.
+    pub fn resolve_complex_restrictions(&mut self) -> Result<(), SchemaError> {
+        let mut pending = std::mem::take(&mut self.shared.pending.restrictions);
+        while !pending.is_empty() {
+            // restrictions of restrictions (also of the bases of extended types) require their
+            // base to be resolved first
+            let mut chains = Vec::with_capacity(pending.len());
+            for restriction in &pending {
+                let Some(Root::Element(base)) = self.roots.get(&restriction.base) else {
+                    return Err(SchemaError::NotFound {
+                        name: restriction.base.name.clone(),
+                    });
+                };
+                let mut chain = vec![&restriction.base];
+                chain.extend(base.bases(&self.roots).into_iter().map(|(name, _)| name));
+                chains.push(chain);
+            }
+            let Some(next) = chains
+                .iter()
+                .position(|chain| !pending.iter().any(|other| chain.contains(&&other.element)))
+            else {
+                return Err(SchemaError::CircularType {
+                    name: pending[0].element.name.clone(),
+                });
+            };
+            let restriction = pending.remove(next);
+
+            // the attributes of the base include those it inherits from its own bases
+            let (inherited, text) = match self.roots.get(&restriction.base) {
+                Some(Root::Element(base)) => (
+                    base.bases(&self.roots)
+                        .into_iter()
+                        .flat_map(|(_, def)| def.attributes.iter())
+                        .chain(&base.attributes)
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    base.content.clone(),
+                ),
+                _ => {
+                    return Err(SchemaError::NotFound {
+                        name: restriction.base.name.clone(),
+                    })
+                }
+            };
+            let content = match (restriction.facets, text) {
+                (None, _) => None,
+                (Some(facets), Some(ElementContent::Leaf(_, text))) => {
+                    Some(self.restrict_text(&restriction.element, text, facets))
+                }
+                (Some(_), _) => {
+                    return Err(SchemaError::InvalidBase {
+                        name: restriction.element.name.clone(),
+                        base: restriction.base.name.clone(),
+                    })
+                }
+            };
+            let Some(Root::Element(def)) = self.roots.get_mut(&restriction.element) else {
+                return Err(SchemaError::NotFound {
+                    name: restriction.element.name.clone(),
+                });
+            };
+
+            let mut attributes = inherited
+                .into_iter()
+                .filter(|attr| {
+                    !restriction.prohibited.contains(&attr.name)
+                        && !def.attributes.iter().any(|own| own.name == attr.name)
+                })
+                .collect::<Vec<_>>();
+            attributes.append(&mut def.attributes);
+            def.attributes = attributes;
+            if content.is_some() {
+                def.content = content;
+            }
+
+            let text = match &def.content {
+                Some(ElementContent::Leaf(_, text)) => Some(&text.content),
+                _ => None,
+            };
+            let dependencies = def
+                .attributes
+                .iter()
+                .map(|attr| &attr.content)
+                .chain(text)
+                .filter_map(|content| match content {
+                    LeafContent::Named(name) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for name in dependencies {
+                self.discover_type(&name, Some(&restriction.element));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the facets of a simple content restriction to the text of its base.
+    fn restrict_text(
+        &self,
+        element: &Name,
+        mut text: LeafDefinition,
+        facets: Vec<Restriction>,
+    ) -> ElementContent {
+        // inline named simple types (e.g. `<xs:extension base="Price">`) to combine their facets
+        while let LeafContent::Named(name) = &text.content {
+            let Some(Root::Leaf(base)) = self.roots.get(name) else {
+                break;
+            };
+            text.content = base.content.clone();
+            text.restrictions
+                .splice(0..0, base.restrictions.iter().cloned());
+        }
+        text.restrictions.extend(facets);
+
+        // the text is named after the element unless it is a named type
+        let name = match &text.content {
+            LeafContent::Named(name) => name.clone(),
+            _ => element.clone(),
+        };
+        ElementContent::Leaf(name, text)
+    }
+
     pub fn into_schema(self) -> Schema {
+        let mut context = SchemaContext {
+            target_namespace: self.target_namespace(),
+            elements: self.roots,
+            namespaces: self.shared.namespaces,
+            identity_constraints: self.identity_constraints,
+            modules: HashMap::new(),
+            options: Options::default(),
+        };
+        context.assign_modules();
+
         Schema {
-            context: SchemaContext {
-                target_namespace: self.target_namespace(),
-                elements: self.roots,
-                is_qualified: self.is_qualified,
-                namespaces: self.shared.namespaces,
-            },
+            context,
             dependencies: self.shared.dependencies,
+            pending: self.shared.pending,
+            files: self.shared.files,
         }
     }
 
@@ -102,8 +951,21 @@ impl<'input> Context<'input> {
         )
     }
 
+    pub fn is_attribute_qualified(&self) -> bool {
+        self.is_attribute_qualified
+    }
+
     pub fn get_type_name(&mut self, attr: &Attribute<'_, '_>) -> Result<LeafContent, XsdError> {
-        let type_name = attr.value();
+        self.resolve_type_name(&attr.value(), attr.range())
+    }
+
+    /// Resolves the given qualified type name (`range` is the location of the name, used for
+    /// errors).
+    pub fn resolve_type_name(
+        &mut self,
+        type_name: &str,
+        range: Range<usize>,
+    ) -> Result<LeafContent, XsdError> {
         let mut parts = type_name.splitn(2, ':');
 
         let name = match (parts.next(), parts.next()) {
@@ -111,14 +973,14 @@ impl<'input> Context<'input> {
                 let ns = self.document_namespaces.get(prefix).ok_or_else(|| {
                     XsdError::MissingNamespace {
                         prefix: prefix.to_string(),
-                        range: attr.range(),
+                        range: range.clone(),
                     }
                 })?;
                 return if *ns == NS_XSD {
                     Ok(LeafContent::Literal(literal_from_str(name).ok_or_else(
                         || XsdError::UnsupportedType {
                             name: name.to_string(),
-                            range: attr.range(),
+                            range: range.clone(),
                         },
                     )?))
                 } else {
@@ -129,15 +991,17 @@ impl<'input> Context<'input> {
                 };
             }
             (Some(name), None) => name,
-            _ => &type_name,
+            _ => type_name,
         };
         if self.default_namespace == Some(NS_XSD) {
             Ok(LeafContent::Literal(literal_from_str(name).ok_or_else(
                 || XsdError::UnsupportedType {
                     name: name.to_string(),
-                    range: attr.range(),
+                    range: range.clone(),
                 },
             )?))
+        } else if self.is_chameleon {
+            Ok(LeafContent::Named(Name::new(name, self.target_namespace)))
         } else {
             Ok(LeafContent::Named(Name::new(name, Namespace::None)))
         }
@@ -146,23 +1010,96 @@ impl<'input> Context<'input> {
     pub fn target_namespace(&self) -> Namespace {
         self.target_namespace
     }
+
+    pub fn target_namespace_uri(&self) -> Option<&str> {
+        match self.target_namespace {
+            Namespace::None => None,
+            Namespace::Id(id) => Some(&self.shared.namespaces.get_by_id(id).namespace),
+        }
+    }
 }
 
 impl SchemaContext {
-    pub fn get_xml_element_name(&self, name: &Name) -> String {
+    /// The name of an element, attribute or type, which is prefixed if it is in a namespace. Only
+    /// elements of the default namespace of a document are written without their prefix (see
+    /// `::xsd::Context::child`), as attributes without a prefix are never in a namespace.
+    pub fn get_xml_name(&self, name: &Name) -> String {
         match &name.namespace {
             Namespace::None => name.name.clone(),
             Namespace::Id(id) => {
-                if self.is_qualified && name.namespace == self.target_namespace {
-                    name.name.clone()
-                } else {
-                    let ns = self.namespaces.get_by_id(*id);
-                    format!("{}:{}", ns.prefix, name.name)
-                }
+                let ns = self.namespaces.get_by_id(*id);
+                format!("{}:{}", ns.prefix, name.name)
             }
         }
     }
 
+    /// Assigns a submodule to every namespace with generated types. Unless configured in the
+    /// options, the module is named after the last segment of the namespace URI (e.g. `common`
+    /// for `http://example.com/schemas/common` or `urn:common`).
+    pub fn assign_modules(&mut self) {
+        let mut ids = self
+            .elements
+            .keys()
+            .filter_map(|name| match name.namespace {
+                Namespace::Id(id) => Some(id),
+                Namespace::None => None,
+            })
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        self.modules.clear();
+        let mut used = HashSet::new();
+        // configured names take precedence over derived ones
+        for &id in &ids {
+            let namespace = &self.namespaces.get_by_id(id).namespace;
+            if let Some(module) = self.options.namespace_modules.get(namespace) {
+                used.insert(module.clone());
+                self.modules.insert(id, module.clone());
+            }
+        }
+        for id in ids {
+            if self.modules.contains_key(&id) {
+                continue;
+            }
+            let namespace = &self.namespaces.get_by_id(id).namespace;
+            let segment = namespace
+                .rsplit(['/', ':', '#'])
+                .map(|segment| segment.strip_suffix(".xsd").unwrap_or(segment))
+                .find(|segment| segment.chars().any(char::is_alphabetic))
+                .unwrap_or_default();
+            let mut module = segment
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+                .to_snake_case();
+            if !module.starts_with(char::is_alphabetic) {
+                module = format!("ns{id}");
+            }
+            if !used.insert(module.clone()) {
+                module = format!("{module}{id}");
+                used.insert(module.clone());
+            }
+            self.modules.insert(id, module);
+        }
+    }
+
+    /// The path of the type generated for the root `name`, which is qualified with the submodule
+    /// of its namespace (e.g. `common::Address`).
+    pub fn type_path(&self, name: &Name) -> TokenStream {
+        let ident = name.to_impl();
+        match name.namespace {
+            Namespace::Id(id) => match self.modules.get(&id) {
+                Some(module) => {
+                    let module = escape_ident(module);
+                    quote!(#module::#ident)
+                }
+                None => ident,
+            },
+            Namespace::None => ident,
+        }
+    }
+
     pub fn quote_xml_namespace(&self, name: &Name) -> TokenStream {
         match &name.namespace {
             Namespace::None => quote!(None),
@@ -187,6 +1124,49 @@ impl SchemaContext {
     }
 }
 
+/// Replaces all references to the keys of `renames` by the corresponding values.
+fn rename_references(
+    roots: &mut HashMap<Name, Root>,
+    dependencies: &mut HashMap<Name, HashSet<Name>>,
+    pending: &mut PendingReferences,
+    renames: &HashMap<Name, Name>,
+) {
+    if renames.is_empty() {
+        return;
+    }
+    let rename = |name: &mut Name| {
+        if let Some(to) = renames.get(name) {
+            *name = to.clone();
+        }
+    };
+
+    for root in roots.values_mut() {
+        root.rename_references(renames);
+    }
+    for dependencies in dependencies.values_mut() {
+        *dependencies = std::mem::take(dependencies)
+            .into_iter()
+            .map(|name| renames.get(&name).cloned().unwrap_or(name))
+            .collect();
+    }
+
+    for group in pending.attribute_groups.values_mut() {
+        group.groups.iter_mut().for_each(rename);
+        for attr in &mut group.attributes {
+            attr.content.rename(renames);
+        }
+    }
+    for (_, group) in &mut pending.attribute_group_refs {
+        rename(group);
+    }
+    for restriction in &mut pending.restrictions {
+        rename(&mut restriction.base);
+    }
+    for (base, _) in &mut pending.derived_types {
+        rename(base);
+    }
+}
+
 fn literal_from_str(literal: &str) -> Option<LiteralType> {
     Some(match literal {
         "string" => LiteralType::String,
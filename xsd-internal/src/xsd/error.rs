@@ -51,6 +51,14 @@ pub enum XsdError {
     },
     #[error("Unsupported XSD type {name}")]
     UnsupportedType { name: String, range: Range<usize> },
+    #[error("Invalid pattern `{pattern}`: {err}")]
+    InvalidPattern {
+        pattern: String,
+        err: regex::Error,
+        range: Range<usize>,
+    },
+    #[error("Invalid or unsupported XPath `{xpath}`")]
+    InvalidXPath { xpath: String, range: Range<usize> },
 }
 
 impl XsdError {
@@ -69,6 +77,8 @@ impl XsdError {
             XsdError::ParseInt { range, .. } => Some(range),
             XsdError::ParseDecimal { range, .. } => Some(range),
             XsdError::UnsupportedType { range, .. } => Some(range),
+            XsdError::InvalidPattern { range, .. } => Some(range),
+            XsdError::InvalidXPath { range, .. } => Some(range),
         }
     }
 }
@@ -2,4 +2,5 @@ pub mod context;
 pub mod error;
 pub mod node;
 pub mod parse;
+pub mod resolver;
 pub mod schema;
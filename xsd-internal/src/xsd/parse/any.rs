@@ -0,0 +1,90 @@
+use inflector::Inflector;
+
+use crate::ast::{Leaf, LeafContent, LeafDefinition, Name, Namespace, Wildcard};
+use crate::xsd::context::{Context, NS_XSD};
+use crate::xsd::error::XsdError;
+use crate::xsd::node::Node;
+
+use super::element::{parse_max_occurs, parse_min_occurs};
+
+/// Parses an `<xs:any>` of a sequence or choice. The wildcard is only named once all particles
+/// are known (see [name_wildcards]).
+pub fn parse(node: Node<'_, '_>, ctx: &mut Context<'_>) -> Result<Leaf, XsdError> {
+    let min_occurs = parse_min_occurs(node.attribute("minOccurs"))?;
+    let max_occurs = parse_max_occurs(node.attribute("maxOccurs"))?;
+
+    let wildcard = parse_wildcard(&node, ctx);
+    node.prevent_unvisited_attributes()?;
+
+    let docs = super::parse_annotation(node.child("annotation", Some(NS_XSD)))?;
+
+    Ok(Leaf {
+        name: Name::new("any", Namespace::None),
+        definition: LeafDefinition {
+            content: LeafContent::Wildcard(wildcard),
+            restrictions: Vec::new(),
+            docs,
+        },
+        is_unordered: false,
+        is_virtual: false,
+        is_nillable: false,
+        min_occurs,
+        max_occurs,
+        default: None,
+    })
+}
+
+/// Numbers the wildcards among the particles of a sequence or choice (`any`, `any2`, ...) to give
+/// each its own field or variant, which must not clash with the elements of the same name.
+pub fn name_wildcards(particles: &mut [Leaf], ctx: &Context<'_>) {
+    let is_wildcard = |leaf: &Leaf| matches!(leaf.definition.content, LeafContent::Wildcard(_));
+    let mut taken = particles
+        .iter()
+        .filter(|leaf| !is_wildcard(leaf))
+        .map(|leaf| leaf.name.name.to_snake_case())
+        .collect::<Vec<_>>();
+
+    let base = Name::new("any", Namespace::None);
+    for leaf in particles.iter_mut().filter(|leaf| is_wildcard(leaf)) {
+        let mut name = base.clone();
+        let mut n = 1;
+        while taken.contains(&name.name.to_snake_case()) {
+            n += 1;
+            name = super::derive_virtual_name(
+                vec![&base, &Name::new(n.to_string(), Namespace::None)],
+                ctx,
+                false,
+            );
+        }
+        taken.push(name.name.to_snake_case());
+        leaf.name = name;
+    }
+}
+
+/// Parses an `<xs:anyAttribute>`.
+pub fn parse_attribute(node: Node<'_, '_>, ctx: &mut Context<'_>) -> Result<Wildcard, XsdError> {
+    let wildcard = parse_wildcard(&node, ctx);
+    node.prevent_unvisited_attributes()?;
+
+    Ok(wildcard)
+}
+
+fn parse_wildcard(node: &Node<'_, '_>, ctx: &mut Context<'_>) -> Wildcard {
+    // the matched content is captured as it is, so there is nothing to validate
+    node.attribute("processContents");
+
+    let target_namespace = ctx.target_namespace_uri().map(String::from);
+    match node.attribute("namespace").map(|a| a.value()).as_deref() {
+        None | Some("##any") => Wildcard::Any,
+        Some("##other") => Wildcard::Other(target_namespace),
+        Some(list) => Wildcard::List(
+            list.split_whitespace()
+                .map(|ns| match ns {
+                    "##targetNamespace" => target_namespace.clone(),
+                    "##local" => None,
+                    ns => Some(ns.to_string()),
+                })
+                .collect(),
+        ),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ast::{Attribute, LeafContent, LeafDefinition, Name, Namespace, Root};
+use crate::ast::{Attribute, LeafContent, Name, Namespace};
 use crate::xsd::context::{Context, NS_XSD};
 use crate::xsd::error::XsdError;
 use crate::xsd::node::Node;
@@ -11,32 +11,23 @@ pub fn parse<'a, 'input>(
 where
     'a: 'input,
 {
-    let name = Name::new(node.try_attribute("name")?.value(), Namespace::None);
+    let name = parse_name(&node, ctx)?;
+
+    // prohibited attributes (used to remove attributes in restrictions) don't need a type
+    if node.attribute("use").map(|attr| attr.value()).as_deref() == Some("prohibited") {
+        return Ok(None);
+    }
 
     let mut children = node.children().namespace(NS_XSD).collect();
     let docs = super::parse_annotation(children.remove("annotation", Some(NS_XSD)))?;
 
     let content = if let Some(child) = children.remove("simpleType", Some(NS_XSD)) {
-        let root = super::simple_type::parse(child, ctx)?;
-        if let Root::Leaf(LeafDefinition {
-            content: LeafContent::Literal(content),
-            ..
-        }) = root
-        {
-            // NOTEN: flatten the type is only fine as long as we don't have sepcial handling
-            // for restrictions
-            LeafContent::Literal(content)
-        } else {
-            let virtual_name = super::derive_virtual_name(
-                vec![parent, &name, &ctx.get_node_name("Data", false)],
-                ctx,
-                false,
-            );
-            ctx.add_root(virtual_name.clone(), root);
-            ctx.discover_type(&virtual_name, Some(parent));
-
-            LeafContent::Named(virtual_name)
-        }
+        let virtual_name = super::derive_virtual_name(
+            vec![parent, &name, &ctx.get_node_name("Data", false)],
+            ctx,
+            false,
+        );
+        super::parse_inline_simple_type(child, &virtual_name, parent, ctx)?
     } else {
         let type_attr = node.try_attribute("type")?;
         let mut content = ctx.get_type_name(type_attr)?;
@@ -59,7 +50,6 @@ where
     let is_optional = match node.attribute("use").map(|attr| attr.value()).as_deref() {
         Some("required") => false,
         Some("optional") | None => true,
-        Some("prohibited") => return Ok(None),
         Some(val) => {
             return Err(XsdError::UnsupportedAttributeValue {
                 name: "use".to_string(),
@@ -87,3 +77,41 @@ where
         docs,
     }))
 }
+
+/// The name of a local attribute, which is only in the target namespace if it is qualified
+/// (by its `form` or the `attributeFormDefault` of the schema).
+fn parse_name(node: &Node<'_, '_>, ctx: &Context<'_>) -> Result<Name, XsdError> {
+    let name = node.try_attribute("name")?.value();
+    let is_qualified = match node.attribute("form").map(|attr| attr.value()).as_deref() {
+        Some("qualified") => true,
+        Some("unqualified") => false,
+        None => ctx.is_attribute_qualified(),
+        Some(val) => {
+            return Err(XsdError::UnsupportedAttributeValue {
+                name: "form".to_string(),
+                value: val.to_owned(),
+                element: node.name().to_string(),
+                range: node.range(),
+            })
+        }
+    };
+    let namespace = if is_qualified {
+        ctx.target_namespace()
+    } else {
+        Namespace::None
+    };
+    Ok(Name::new(name, namespace))
+}
+
+/// The names of all attributes of a `<xs:restriction>` that are removed from its base type.
+pub fn parse_prohibited(node: &Node<'_, '_>, ctx: &Context<'_>) -> Result<Vec<Name>, XsdError> {
+    node.children()
+        .namespace(NS_XSD)
+        .iter()
+        .filter(|child| {
+            child.name() == "attribute"
+                && child.attribute("use").map(|attr| attr.value()).as_deref() == Some("prohibited")
+        })
+        .map(|child| parse_name(&child, ctx))
+        .collect()
+}
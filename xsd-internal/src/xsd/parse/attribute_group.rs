@@ -0,0 +1,72 @@
+use crate::ast::{AttributeGroup, LeafContent, Name};
+use crate::xsd::context::{Context, NS_XSD};
+use crate::xsd::error::XsdError;
+use crate::xsd::node::Node;
+
+/// Parses a top-level `<xs:attributeGroup>`. Its attributes are added to the referencing elements
+/// once all schemas are parsed (see [Context::resolve_attribute_groups]).
+pub fn parse<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<(), XsdError>
+where
+    'a: 'input,
+{
+    node.prevent_unvisited_attributes()?;
+
+    let mut children = node.children().namespace(NS_XSD).collect();
+    // TODO: docs of attribute groups have nowhere to go
+    super::parse_annotation(children.remove("annotation", Some(NS_XSD)))?;
+
+    let mut group = AttributeGroup::default();
+    while let Some(child) = children.remove("attribute", Some(NS_XSD)) {
+        if let Some(attr) = super::attribute::parse(child, name, ctx)? {
+            group.attributes.push(attr);
+        }
+    }
+    while let Some(child) = children.remove("attributeGroup", Some(NS_XSD)) {
+        group.groups.push(parse_ref_name(child, ctx)?);
+    }
+    group.any_attribute = children
+        .remove("anyAttribute", Some(NS_XSD))
+        .map(|child| super::any::parse_attribute(child, ctx))
+        .transpose()?;
+
+    children.prevent_unvisited_children()?;
+
+    ctx.add_attribute_group(name.clone(), group);
+
+    Ok(())
+}
+
+/// Parses a `<xs:attributeGroup ref="" />` inside of the element `parent`.
+pub fn parse_ref<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<(), XsdError>
+where
+    'a: 'input,
+{
+    let name = parse_ref_name(node, ctx)?;
+    ctx.add_attribute_group_ref(parent, name);
+    Ok(())
+}
+
+fn parse_ref_name(node: Node<'_, '_>, ctx: &mut Context<'_>) -> Result<Name, XsdError> {
+    let attr = node.try_attribute("ref")?;
+    let name = match ctx.get_type_name(attr)? {
+        LeafContent::Named(name) => name,
+        _ => {
+            return Err(XsdError::UnsupportedAttributeValue {
+                name: "ref".to_string(),
+                value: attr.value().to_string(),
+                element: "attributeGroup".to_string(),
+                range: attr.range(),
+            })
+        }
+    };
+    node.prevent_unvisited_attributes()?;
+    Ok(name)
+}
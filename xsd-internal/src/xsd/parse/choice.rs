@@ -21,6 +21,8 @@ where
     for child in node.children().namespace(NS_XSD).iter() {
         let variant = match child.name() {
             "element" => super::element::parse_child(child, parent, ctx)?,
+            "any" => super::any::parse(child, ctx)?,
+            "group" => super::group::parse_ref(child, parent, ctx)?,
             "sequence" => {
                 let docs = super::parse_annotation(child.child("annotation", Some(NS_XSD)))?;
                 let min_occurs = parse_min_occurs(child.attribute("minOccurs"))?;
@@ -41,6 +43,8 @@ where
                         attributes: Vec::new(),
                         content: Some(ElementContent::Leaves(leaves)),
                         is_virtual: true,
+                        is_mixed: false,
+                        any_attribute: None,
                         docs: None,
                     }),
                 );
@@ -55,8 +59,10 @@ where
                     },
                     is_unordered: false,
                     is_virtual: true,
+                    is_nillable: false,
                     min_occurs,
                     max_occurs,
+                    default: None,
                 }
             }
             "annotation" => continue,
@@ -70,5 +76,7 @@ where
         variants.push(variant);
     }
 
+    super::any::name_wildcards(&mut variants, ctx);
+
     Ok(variants)
 }
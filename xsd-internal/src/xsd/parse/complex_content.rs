@@ -2,7 +2,7 @@ use crate::ast::{
     ElementContent, ElementDefinition, Leaf, LeafContent, LeafDefinition, MaxOccurs, MinOccurs,
     Name, Root,
 };
-use crate::xsd::context::{Context, NS_XSD};
+use crate::xsd::context::{ComplexRestriction, Context, NS_XSD};
 use crate::xsd::error::XsdError;
 use crate::xsd::node::Node;
 
@@ -19,13 +19,17 @@ where
     node.prevent_unvisited_attributes()?;
 
     let mut children = node.children().namespace(NS_XSD).collect();
+    if let Some(restriction) = children.remove("restriction", Some(NS_XSD)) {
+        children.prevent_unvisited_children()?;
+        return parse_restriction(restriction, parent, ctx);
+    }
     let extension = children.try_remove("extension", Some(NS_XSD))?;
     children.prevent_unvisited_children()?;
 
     let base = extension.try_attribute("base")?;
     let content = ctx.get_type_name(base)?;
     let base_name = match &content {
-        LeafContent::Literal(_) | LeafContent::Fixed(_) => {
+        LeafContent::Literal(_) | LeafContent::Fixed(_) | LeafContent::Wildcard(_) => {
             return Err(XsdError::UnsupportedAttributeValue {
                 name: "base".to_string(),
                 value: base.value().to_string(),
@@ -55,6 +59,8 @@ where
                 attributes: Vec::new(),
                 content: Some(ElementContent::Leaves(leaves)),
                 is_virtual: true,
+                is_mixed: false,
+                any_attribute: None,
                 docs: None,
             }),
         );
@@ -69,9 +75,13 @@ where
             },
             is_unordered: false,
             is_virtual: true,
+            is_nillable: false,
             min_occurs,
             max_occurs: MaxOccurs::default(),
+            default: None,
         });
+    } else if let Some(child) = children.remove("group", Some(NS_XSD)) {
+        virtual_leaves.push(super::group::parse_ref(child, parent, ctx)?);
     }
 
     // read all attributes
@@ -81,6 +91,13 @@ where
             attributes.push(attr);
         }
     }
+    while let Some(child) = children.remove("attributeGroup", Some(NS_XSD)) {
+        super::attribute_group::parse_ref(child, parent, ctx)?;
+    }
+    let any_attribute = children
+        .remove("anyAttribute", Some(NS_XSD))
+        .map(|child| super::any::parse_attribute(child, ctx))
+        .transpose()?;
 
     children.prevent_unvisited_children()?;
 
@@ -106,13 +123,56 @@ where
                 },
                 is_unordered: false,
                 is_virtual: true,
+                is_nillable: false,
                 min_occurs: MinOccurs::default(),
                 max_occurs: MaxOccurs::default(),
+                default: None,
             }];
             leaves.extend(virtual_leaves);
             ElementContent::Leaves(leaves)
         }),
         is_virtual: false,
+        is_mixed: false,
+        any_attribute,
         docs: None,
     })
 }
+
+/// A restriction is a standalone copy of the base type, as it has to repeat all elements of the
+/// base anyway. Only the attributes are inherited from the base (once it is known), as they are
+/// kept unless they are prohibited.
+fn parse_restriction<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<ElementDefinition, XsdError>
+where
+    'a: 'input,
+{
+    let base = node.try_attribute("base")?;
+    let base = ctx.get_type_name(base)?;
+
+    let prohibited = super::attribute::parse_prohibited(&node, ctx)?;
+
+    let mut def = match super::complex_type::parse(node, parent, ctx)? {
+        Root::Element(def) => def,
+        _ => unreachable!("complex types are always parsed into elements"),
+    };
+
+    // elements that are removed by the restriction
+    if let Some(ElementContent::Leaves(leaves)) = &mut def.content {
+        leaves.retain(|leaf| leaf.max_occurs != MaxOccurs::Number(0));
+    }
+
+    // a restriction of `xs:anyType` is the same as a complex type without a base
+    if let LeafContent::Named(base) = base {
+        ctx.add_complex_restriction(ComplexRestriction {
+            element: parent.clone(),
+            base,
+            prohibited,
+            facets: None,
+        });
+    }
+
+    Ok(def)
+}
@@ -1,6 +1,6 @@
 use crate::ast::{
-    ChoiceDefinition, ElementContent, ElementDefinition, Leaf, LeafContent, LeafDefinition, Name,
-    Root,
+    ChoiceDefinition, ElementContent, ElementDefinition, Leaf, LeafContent, LeafDefinition,
+    MaxOccurs, MinOccurs, MixedContent, ModelGroup, Name, Root,
 };
 use crate::xsd::context::{Context, NS_XSD};
 use crate::xsd::error::XsdError;
@@ -8,7 +8,9 @@ use crate::xsd::node::Node;
 
 use super::element::{parse_max_occurs, parse_min_occurs};
 
-pub fn parse<'a, 'input>(
+/// Parses a top-level `<xs:complexType>`, which (unlike anonymous types) can be used in place of
+/// its base type by specifying it as `xsi:type`.
+pub fn parse_named<'a, 'input>(
     node: Node<'a, 'input>,
     parent: &Name,
     ctx: &mut Context<'input>,
@@ -16,14 +18,35 @@ pub fn parse<'a, 'input>(
 where
     'a: 'input,
 {
-    if node.attribute("mixed").map(|a| a.value()).as_deref() == Some("true") {
-        return Err(XsdError::UnsupportedAttributeValue {
-            name: "mixed".to_string(),
-            value: "true".to_string(),
-            element: node.name().to_string(),
-            range: node.range(),
+    let derivation = node
+        .child("complexContent", Some(NS_XSD))
+        .or_else(|| node.child("simpleContent", Some(NS_XSD)))
+        .and_then(|content| {
+            content
+                .child("extension", Some(NS_XSD))
+                .or_else(|| content.child("restriction", Some(NS_XSD)))
         });
+    if let Some(derivation) = derivation {
+        if let Some(attr) = derivation.attribute("base") {
+            if let LeafContent::Named(base) = ctx.get_type_name(attr)? {
+                ctx.add_derived_type(base, parent);
+            }
+        }
     }
+
+    parse(node, parent, ctx)
+}
+
+pub fn parse<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<Root, XsdError>
+where
+    'a: 'input,
+{
+    let mixed = node.attribute("mixed");
+    let is_mixed = matches!(mixed.map(|a| a.value()).as_deref(), Some("true" | "1"));
     node.prevent_unvisited_attributes()?;
 
     let mut children = node.children().namespace(NS_XSD).collect();
@@ -31,6 +54,18 @@ where
     // TODO: (annotation?,(simpleContent|complexContent|)
     // TODO: simpleContent xor complexContent xor the following
 
+    if is_mixed
+        && (children.remove("simpleContent", Some(NS_XSD)).is_some()
+            || children.remove("complexContent", Some(NS_XSD)).is_some())
+    {
+        return Err(XsdError::UnsupportedAttributeValue {
+            name: "mixed".to_string(),
+            value: mixed.map(|a| a.value().into_owned()).unwrap_or_default(),
+            element: node.name().to_string(),
+            range: node.range(),
+        });
+    }
+
     if let Some(child) = children.remove("simpleContent", Some(NS_XSD)) {
         children.prevent_unvisited_children()?;
         return Ok(Root::Element(
@@ -44,7 +79,90 @@ where
         ));
     }
 
-    let content = if let Some(child) = children
+    let content = if is_mixed {
+        // Mixed content is represented as a list of the child elements and the text nodes
+        // between them (in the order they appear in the document).
+        let particle = children
+            .remove("sequence", Some(NS_XSD))
+            .or_else(|| children.remove("all", Some(NS_XSD)))
+            .or_else(|| children.remove("choice", Some(NS_XSD)));
+        let (mut variants, mixed) = match particle {
+            Some(child) => {
+                let group = match child.name() {
+                    "sequence" => ModelGroup::Sequence,
+                    "all" => ModelGroup::All,
+                    _ => ModelGroup::Choice,
+                };
+                let min_occurs = parse_min_occurs(child.attribute("minOccurs"))?;
+                let max_occurs = parse_max_occurs(child.attribute("maxOccurs"))?;
+                let variants = if group == ModelGroup::Choice {
+                    super::choice::parse(child, parent, ctx)?
+                } else {
+                    super::sequence::parse(child, parent, ctx)?
+                };
+                let occurs = variants
+                    .iter()
+                    .map(|variant| (variant.min_occurs, variant.max_occurs))
+                    .collect();
+                (
+                    variants,
+                    MixedContent {
+                        group,
+                        min_occurs,
+                        max_occurs,
+                        occurs,
+                    },
+                )
+            }
+            None => (
+                Vec::new(),
+                MixedContent {
+                    group: ModelGroup::Sequence,
+                    min_occurs: MinOccurs::default(),
+                    max_occurs: MaxOccurs::default(),
+                    occurs: Vec::new(),
+                },
+            ),
+        };
+        // the elements are decoded one at a time and checked against the occurrences afterwards
+        for variant in &mut variants {
+            variant.is_unordered = false;
+            variant.min_occurs = MinOccurs::default();
+            variant.max_occurs = MaxOccurs::default();
+        }
+
+        let root_name = super::derive_virtual_name(
+            vec![parent, &ctx.get_node_name("Content", false)],
+            ctx,
+            false,
+        );
+
+        ctx.add_root(
+            root_name.clone(),
+            Root::Choice(ChoiceDefinition {
+                variants,
+                is_virtual: true,
+                mixed: Some(mixed),
+                docs: None,
+            }),
+        );
+        ctx.discover_type(&root_name, Some(parent));
+
+        Some(ElementContent::Leaves(vec![Leaf {
+            name: ctx.get_node_name("content", false),
+            definition: LeafDefinition {
+                content: LeafContent::Named(root_name),
+                restrictions: Vec::new(),
+                docs: None,
+            },
+            is_unordered: false,
+            is_virtual: true,
+            is_nillable: false,
+            min_occurs: MinOccurs::default(),
+            max_occurs: MaxOccurs::Unbounded,
+            default: None,
+        }]))
+    } else if let Some(child) = children
         .remove("sequence", Some(NS_XSD))
         .or_else(|| children.remove("all", Some(NS_XSD)))
     {
@@ -68,6 +186,8 @@ where
                     attributes: Vec::new(),
                     content: Some(ElementContent::Leaves(leaves)),
                     is_virtual: true,
+                    is_mixed: false,
+                    any_attribute: None,
                     docs: None,
                 }),
             );
@@ -82,8 +202,10 @@ where
                 },
                 is_unordered: false,
                 is_virtual: true,
+                is_nillable: false,
                 min_occurs,
                 max_occurs,
+                default: None,
             }]))
         } else {
             Some(ElementContent::Leaves(leaves))
@@ -98,6 +220,7 @@ where
             Root::Choice(ChoiceDefinition {
                 variants,
                 is_virtual: false,
+                mixed: None,
                 docs: None,
             }),
         );
@@ -111,6 +234,10 @@ where
                 docs: None,
             },
         ))
+    } else if let Some(child) = children.remove("group", Some(NS_XSD)) {
+        Some(ElementContent::Leaves(vec![super::group::parse_ref(
+            child, parent, ctx,
+        )?]))
     } else {
         None
     };
@@ -122,6 +249,13 @@ where
             attributes.push(attr);
         }
     }
+    while let Some(child) = children.remove("attributeGroup", Some(NS_XSD)) {
+        super::attribute_group::parse_ref(child, parent, ctx)?;
+    }
+    let any_attribute = children
+        .remove("anyAttribute", Some(NS_XSD))
+        .map(|child| super::any::parse_attribute(child, ctx))
+        .transpose()?;
 
     children.prevent_unvisited_children()?;
 
@@ -129,6 +263,8 @@ where
         attributes,
         content,
         is_virtual: false,
+        is_mixed,
+        any_attribute,
         docs,
     }))
 }
@@ -18,6 +18,38 @@ where
     let mut children = node.children().namespace(NS_XSD).collect();
     let docs = super::parse_annotation(children.remove("annotation", Some(NS_XSD)))?;
 
+    // the constraints of local elements (with inline types) are parsed by `parse_child`
+    for name in ["key", "keyref", "unique"] {
+        while children.remove(name, Some(NS_XSD)).is_some() {}
+    }
+    if node
+        .parent_element()
+        .is_some_and(|parent| matches!(parent.tag_name().name(), "schema" | "override"))
+    {
+        let constraints = super::identity_constraint::parse_all(&node, parent, ctx)?;
+        ctx.add_identity_constraints(parent, constraints);
+    }
+
+    if let Some(attr) = node.attribute("substitutionGroup") {
+        match ctx.get_type_name(attr)? {
+            LeafContent::Named(head) => ctx.add_substitution_group_member(head, parent),
+            _ => {
+                return Err(XsdError::UnsupportedAttributeValue {
+                    name: "substitutionGroup".to_string(),
+                    value: attr.value().to_string(),
+                    element: node.name().to_string(),
+                    range: attr.range(),
+                })
+            }
+        }
+    }
+    if parse_boolean(&node, "abstract") {
+        ctx.add_abstract_element(parent);
+    }
+    if parse_boolean(&node, "nillable") {
+        ctx.add_nillable_element(parent);
+    }
+
     // <element type="xs:string" /> | <element type="MyCustomType" />
     if let Some(attr) = node.attribute("type") {
         let mut content = ctx.get_type_name(attr)?;
@@ -45,7 +77,7 @@ where
         let result = if let Some(child) = children.remove("complexType", Some(NS_XSD)) {
             super::complex_type::parse(child, parent, ctx)?
         } else if let Some(child) = children.remove("simpleType", Some(NS_XSD)) {
-            super::simple_type::parse(child, ctx)?
+            super::simple_type::parse(child, parent, ctx)?
         } else {
             return Err(XsdError::MissingElement {
                 name: "simpleType|complexType".to_string(),
@@ -86,8 +118,10 @@ where
                 },
                 is_unordered: false,
                 is_virtual: false,
+                is_nillable: false,
                 min_occurs,
                 max_occurs,
+                default: None,
             });
         } else {
             return Err(XsdError::UnsupportedAttributeValue {
@@ -101,14 +135,17 @@ where
 
     let name = ctx.get_node_name(&node.try_attribute("name")?.value(), false);
 
-    // mark `default` attribute as visited
-    node.attribute("default");
+    let default = node.attribute("default").map(|a| a.value().into_owned());
+    let is_nillable = parse_boolean(&node, "nillable");
 
     // TODO: implement attribute?
     node.attribute("form");
 
     let docs = super::parse_annotation(node.child("annotation", Some(NS_XSD)))?;
 
+    let constraints = super::identity_constraint::parse_all(&node, &name, ctx)?;
+    ctx.add_identity_constraints(parent, constraints);
+
     // <element type="xs:string" /> | <element type="MyCustomType" />
     if let Some(attr) = node.attribute("type") {
         let mut content = ctx.get_type_name(attr)?;
@@ -133,8 +170,10 @@ where
             },
             is_unordered: false,
             is_virtual: false,
+            is_nillable,
             min_occurs,
             max_occurs,
+            default,
         })
     } else if node.child("simpleType", Some(NS_XSD)).is_none()
         && node.child("complexType", Some(NS_XSD)).is_none()
@@ -151,8 +190,10 @@ where
             },
             is_unordered: false,
             is_virtual: false,
+            is_nillable,
             min_occurs,
             max_occurs,
+            default,
         })
     } else {
         node.prevent_unvisited_attributes()?;
@@ -177,12 +218,22 @@ where
             },
             is_unordered: false,
             is_virtual: false,
+            is_nillable,
             min_occurs,
             max_occurs,
+            default,
         })
     }
 }
 
+/// Whether the boolean attribute `name` is set (`true` or `1`).
+fn parse_boolean(node: &Node<'_, '_>, name: &str) -> bool {
+    matches!(
+        node.attribute(name).map(|attr| attr.value()).as_deref(),
+        Some("true" | "1")
+    )
+}
+
 pub fn parse_min_occurs(occurs: Option<&Attribute<'_, '_>>) -> Result<MinOccurs, XsdError> {
     match occurs {
         Some(attr) => Ok(MinOccurs(u32::from_str(&attr.value()).map_err(|err| {
@@ -0,0 +1,116 @@
+use crate::ast::{
+    ChoiceDefinition, ElementContent, ElementDefinition, Leaf, LeafContent, LeafDefinition, Name,
+    Root,
+};
+use crate::xsd::context::{Context, NS_XSD};
+use crate::xsd::error::XsdError;
+use crate::xsd::node::Node;
+
+use super::element::{parse_max_occurs, parse_min_occurs};
+
+/// The name of the root of a top-level group, which is kept apart from the types of the same name.
+/// The name is only provisional (`#` can't be part of a type name), as the final one (e.g.
+/// `ContactGroup` for a group `Contact`) must not replace a type of that name, which may be
+/// defined later (see [Context::resolve_group_names]).
+pub fn group_name(name: &Name) -> Name {
+    Name::new(format!("{}#Group", name.name), name.namespace)
+}
+
+/// Parses a top-level `<xs:group>`, which becomes a virtual struct (for `sequence` and `all`) or a
+/// virtual enum (for `choice`) that is shared by all references of the group.
+pub fn parse<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<Root, XsdError>
+where
+    'a: 'input,
+{
+    node.prevent_unvisited_attributes()?;
+
+    let mut children = node.children().namespace(NS_XSD).collect();
+    let docs = super::parse_annotation(children.remove("annotation", Some(NS_XSD)))?;
+
+    let root = if let Some(child) = children
+        .remove("sequence", Some(NS_XSD))
+        .or_else(|| children.remove("all", Some(NS_XSD)))
+    {
+        let is_unordered = child.name() == "all";
+        let mut leaves = super::sequence::parse(child, parent, ctx)?;
+        if is_unordered {
+            for leaf in &mut leaves {
+                leaf.is_unordered = true;
+            }
+        }
+
+        Root::Element(ElementDefinition {
+            attributes: Vec::new(),
+            content: Some(ElementContent::Leaves(leaves)),
+            is_virtual: true,
+            is_mixed: false,
+            any_attribute: None,
+            docs,
+        })
+    } else if let Some(child) = children.remove("choice", Some(NS_XSD)) {
+        Root::Choice(ChoiceDefinition {
+            variants: super::choice::parse(child, parent, ctx)?,
+            is_virtual: true,
+            mixed: None,
+            docs,
+        })
+    } else {
+        return Err(XsdError::MissingElement {
+            name: "sequence|all|choice".to_string(),
+            parent: node.name().to_string(),
+            range: node.range(),
+        });
+    };
+
+    children.prevent_unvisited_children()?;
+
+    Ok(root)
+}
+
+/// Parses a `<xs:group ref="" />` into a virtual leaf of the referenced group.
+pub fn parse_ref<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<Leaf, XsdError>
+where
+    'a: 'input,
+{
+    let min_occurs = parse_min_occurs(node.attribute("minOccurs"))?;
+    let max_occurs = parse_max_occurs(node.attribute("maxOccurs"))?;
+
+    let attr = node.try_attribute("ref")?;
+    let name = match ctx.get_type_name(attr)? {
+        LeafContent::Named(name) => name,
+        _ => {
+            return Err(XsdError::UnsupportedAttributeValue {
+                name: "ref".to_string(),
+                value: attr.value().to_string(),
+                element: "group".to_string(),
+                range: attr.range(),
+            })
+        }
+    };
+    node.prevent_unvisited_attributes()?;
+    let group = group_name(&name);
+    ctx.discover_type(&group, Some(parent));
+
+    Ok(Leaf {
+        name,
+        definition: LeafDefinition {
+            content: LeafContent::Named(group),
+            restrictions: Vec::new(),
+            docs: None,
+        },
+        is_unordered: false,
+        is_virtual: true,
+        is_nillable: false,
+        min_occurs,
+        max_occurs,
+        default: None,
+    })
+}
@@ -0,0 +1,183 @@
+use crate::ast::{IdentityConstraint, IdentityKind, LeafContent, Name, NameTest, XPath, XPathPath};
+use crate::xsd::context::{Context, NS_XSD};
+use crate::xsd::error::XsdError;
+use crate::xsd::node::{Attribute, Node};
+
+/// Parses all `<xs:key>`, `<xs:keyref>` and `<xs:unique>` children of the given element
+/// declaration (named `element`).
+pub fn parse_all<'a, 'input>(
+    node: &Node<'a, 'input>,
+    element: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<Vec<IdentityConstraint>, XsdError>
+where
+    'a: 'input,
+{
+    node.children()
+        .namespace(NS_XSD)
+        .iter()
+        .filter(|child| matches!(child.name(), "key" | "keyref" | "unique"))
+        .map(|child| parse(child, element, ctx))
+        .collect()
+}
+
+fn parse<'a, 'input>(
+    node: Node<'a, 'input>,
+    element: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<IdentityConstraint, XsdError>
+where
+    'a: 'input,
+{
+    let name = ctx.get_node_name(&node.try_attribute("name")?.value(), true);
+    let kind = match node.name() {
+        "key" => IdentityKind::Key,
+        "unique" => IdentityKind::Unique,
+        _ => {
+            let attr = node.try_attribute("refer")?;
+            match ctx.get_type_name(attr)? {
+                LeafContent::Named(refer) => IdentityKind::KeyRef { refer },
+                _ => {
+                    return Err(XsdError::UnsupportedAttributeValue {
+                        name: "refer".to_string(),
+                        value: attr.value().to_string(),
+                        element: node.name().to_string(),
+                        range: attr.range(),
+                    })
+                }
+            }
+        }
+    };
+    node.prevent_unvisited_attributes()?;
+
+    let mut children = node.children().namespace(NS_XSD).collect();
+    // constraints are not part of the generated code, so their docs are ignored
+    super::parse_annotation(children.remove("annotation", Some(NS_XSD)))?;
+
+    let selector = children.try_remove("selector", Some(NS_XSD))?;
+    let selector_xpath = parse_xpath(&selector, selector.try_attribute("xpath")?, false)?;
+    selector.prevent_unvisited_attributes()?;
+
+    let mut fields = Vec::new();
+    while let Some(field) = children.remove("field", Some(NS_XSD)) {
+        fields.push(parse_xpath(&field, field.try_attribute("xpath")?, true)?);
+        field.prevent_unvisited_attributes()?;
+    }
+    if fields.is_empty() {
+        return Err(XsdError::MissingElement {
+            name: "field".to_string(),
+            parent: node.name().to_string(),
+            range: node.range(),
+        });
+    }
+
+    children.prevent_unvisited_children()?;
+
+    Ok(IdentityConstraint {
+        name,
+        kind,
+        element: element.clone(),
+        selector: selector_xpath,
+        fields,
+    })
+}
+
+/// Parses the restricted XPath of a selector or field, e.g. `.//item | list/entry` or
+/// `entry/@id` (attributes are only allowed in fields).
+fn parse_xpath(
+    node: &Node<'_, '_>,
+    attr: &Attribute<'_, '_>,
+    allow_attribute: bool,
+) -> Result<XPath, XsdError> {
+    let xpath = attr.value().into_owned();
+    let invalid = || XsdError::InvalidXPath {
+        xpath: xpath.clone(),
+        range: attr.range(),
+    };
+
+    let mut paths = Vec::new();
+    for path in xpath.split('|') {
+        let mut path = path.trim();
+        let is_descendant = if let Some(rest) = path.strip_prefix(".//") {
+            path = rest;
+            true
+        } else {
+            false
+        };
+
+        let mut steps = Vec::new();
+        let mut attribute = None;
+        let mut parts = path.split('/').map(str::trim).peekable();
+        while let Some(step) = parts.next() {
+            if let Some(test) = step
+                .strip_prefix('@')
+                .or_else(|| step.strip_prefix("attribute::"))
+            {
+                if !allow_attribute || parts.peek().is_some() {
+                    return Err(invalid());
+                }
+                attribute = Some(parse_name_test(node, attr, test.trim())?);
+            } else if step != "." {
+                let test = step.strip_prefix("child::").unwrap_or(step).trim();
+                steps.push(parse_name_test(node, attr, test)?);
+            }
+        }
+
+        paths.push(XPathPath {
+            is_descendant,
+            steps,
+            attribute,
+        });
+    }
+
+    Ok(XPath { xpath, paths })
+}
+
+/// Parses `*`, `prefix:*`, `name` or `prefix:name`. Unprefixed names do not belong to any
+/// namespace.
+fn parse_name_test(
+    node: &Node<'_, '_>,
+    attr: &Attribute<'_, '_>,
+    test: &str,
+) -> Result<NameTest, XsdError> {
+    if test == "*" {
+        return Ok(NameTest::Any);
+    }
+
+    let (prefix, name) = match test.split_once(':') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, test),
+    };
+    let is_ncname = |s: &str| {
+        s.chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+            && s.chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    };
+    if !prefix.is_none_or(is_ncname) || !(name == "*" && prefix.is_some() || is_ncname(name)) {
+        return Err(XsdError::InvalidXPath {
+            xpath: attr.value().into_owned(),
+            range: attr.range(),
+        });
+    }
+
+    let namespace = match prefix {
+        Some(prefix) => match node.lookup_namespace_uri(Some(prefix)) {
+            Some(uri) => Some(uri.to_string()),
+            None => {
+                return Err(XsdError::MissingNamespace {
+                    prefix: prefix.to_string(),
+                    range: attr.range(),
+                })
+            }
+        },
+        None => None,
+    };
+
+    Ok(if name == "*" {
+        NameTest::Namespace(namespace)
+    } else {
+        NameTest::Name(name.to_string(), namespace)
+    })
+}
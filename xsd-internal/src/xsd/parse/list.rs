@@ -0,0 +1,35 @@
+use crate::ast::{LeafContent, ListDefinition, Name, Root};
+use crate::xsd::context::{Context, NS_XSD};
+use crate::xsd::error::XsdError;
+use crate::xsd::node::Node;
+
+pub fn parse<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<Root, XsdError>
+where
+    'a: 'input,
+{
+    // <list itemType="xs:decimal" />
+    let item = if let Some(attr) = node.attribute("itemType") {
+        let content = ctx.get_type_name(attr)?;
+        if let LeafContent::Named(name) = &content {
+            ctx.discover_type(name, Some(parent));
+        }
+        content
+    } else {
+        // <list><simpleType>...</simpleType></list>
+        let child = node.try_child("simpleType", Some(NS_XSD))?;
+        let virtual_name =
+            super::derive_virtual_name(vec![parent, &ctx.get_node_name("Item", false)], ctx, false);
+        super::parse_inline_simple_type(child, &virtual_name, parent, ctx)?
+    };
+    node.prevent_unvisited_attributes()?;
+
+    Ok(Root::List(ListDefinition {
+        item,
+        restrictions: Vec::new(),
+        docs: None,
+    }))
+}
@@ -1,20 +1,26 @@
 mod annotation;
+mod any;
 mod attribute;
+pub mod attribute_group;
 mod choice;
 mod complex_content;
 pub mod complex_type;
 pub mod element;
+pub mod group;
+mod identity_constraint;
+mod list;
 pub mod root;
 mod sequence;
 mod simple_content;
 pub mod simple_type;
+mod union;
 
 use inflector::Inflector;
 
 use super::context::Context;
 use super::error::XsdError;
 use super::node::Node;
-use crate::ast::Name;
+use crate::ast::{LeafContent, LeafDefinition, Name, Root};
 
 fn derive_virtual_name<'a, 'input, 'b>(
     names: impl IntoIterator<Item = &'b Name> + Clone,
@@ -106,6 +112,33 @@ fn mutal_suffix(lhs: &str, rhs: &str) -> String {
         .collect()
 }
 
+/// Parses an anonymous `<xs:simpleType>` of `parent` (e.g. the items of a list), which becomes
+/// the virtual type `name` unless it is flattened into its literal type.
+fn parse_inline_simple_type<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &Name,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<LeafContent, XsdError>
+where
+    'a: 'input,
+{
+    match simple_type::parse(node, name, ctx)? {
+        // NOTE: flattening the type is only fine as long as there are no restrictions that need to
+        // be checked
+        Root::Leaf(LeafDefinition {
+            content: LeafContent::Literal(content),
+            restrictions,
+            ..
+        }) if restrictions.is_empty() => Ok(LeafContent::Literal(content)),
+        root => {
+            ctx.add_root(name.clone(), root);
+            ctx.discover_type(name, Some(parent));
+            Ok(LeafContent::Named(name.clone()))
+        }
+    }
+}
+
 fn parse_annotation(node: Option<Node<'_, '_>>) -> Result<Option<String>, XsdError> {
     Ok(node.map(annotation::parse).transpose()?.flatten())
 }
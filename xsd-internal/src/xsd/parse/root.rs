@@ -13,8 +13,9 @@ where
 {
     match node.name() {
         "element" => super::element::parse_root(node, parent, ctx),
-        "complexType" => super::complex_type::parse(node, parent, ctx),
-        "simpleType" => super::simple_type::parse(node, ctx),
+        "complexType" => super::complex_type::parse_named(node, parent, ctx),
+        "simpleType" => super::simple_type::parse(node, parent, ctx),
+        "group" => super::group::parse(node, parent, ctx),
         child_name => Err(XsdError::UnsupportedElement {
             name: child_name.to_string(),
             range: node.range(),
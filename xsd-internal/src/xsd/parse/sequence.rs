@@ -23,6 +23,8 @@ where
     for child in node.children().namespace(NS_XSD).iter() {
         let leaf = match child.name() {
             "element" => super::element::parse_child(child, parent, ctx)?,
+            "any" => super::any::parse(child, ctx)?,
+            "group" => super::group::parse_ref(child, parent, ctx)?,
             "choice" => {
                 let min_occurs = parse_min_occurs(child.attribute("minOccurs"))?;
                 let max_occurs = parse_max_occurs(child.attribute("maxOccurs"))?;
@@ -37,6 +39,7 @@ where
                     Root::Choice(ChoiceDefinition {
                         variants,
                         is_virtual: true,
+                        mixed: None,
                         docs: None,
                     }),
                 );
@@ -51,8 +54,10 @@ where
                     },
                     is_unordered: false,
                     is_virtual: true,
+                    is_nillable: false,
                     min_occurs,
                     max_occurs,
+                    default: None,
                 }
             }
             "sequence" => {
@@ -71,6 +76,8 @@ where
                         attributes: Vec::new(),
                         content: Some(ElementContent::Leaves(leaves)),
                         is_virtual: true,
+                        is_mixed: false,
+                        any_attribute: None,
                         docs: None,
                     }),
                 );
@@ -85,8 +92,10 @@ where
                     },
                     is_unordered: false,
                     is_virtual: true,
+                    is_nillable: false,
                     min_occurs,
                     max_occurs: MaxOccurs::default(),
+                    default: None,
                 }
             }
             "annotation" => continue,
@@ -100,5 +109,7 @@ where
         leaves.push(leaf);
     }
 
+    super::any::name_wildcards(&mut leaves, ctx);
+
     Ok(leaves)
 }
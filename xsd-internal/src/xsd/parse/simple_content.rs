@@ -1,8 +1,12 @@
-use crate::ast::{ElementContent, ElementDefinition, LeafContent, LeafDefinition, Name};
-use crate::xsd::context::{Context, NS_XSD};
+use crate::ast::{
+    ElementContent, ElementDefinition, EnumDefinition, LeafContent, LeafDefinition, Name, Root,
+};
+use crate::xsd::context::{ComplexRestriction, Context, NS_XSD};
 use crate::xsd::error::XsdError;
 use crate::xsd::node::Node;
 
+use super::simple_type::Facets;
+
 pub fn parse<'a, 'input>(
     node: Node<'a, 'input>,
     parent: &Name,
@@ -14,6 +18,10 @@ where
     node.prevent_unvisited_attributes()?;
 
     let mut children = node.children().namespace(NS_XSD).collect();
+    if let Some(restriction) = children.remove("restriction", Some(NS_XSD)) {
+        children.prevent_unvisited_children()?;
+        return parse_restriction(restriction, parent, ctx);
+    }
     let extension = children.try_remove("extension", Some(NS_XSD))?;
     children.prevent_unvisited_children()?;
 
@@ -35,6 +43,13 @@ where
             attributes.push(attr);
         }
     }
+    while let Some(child) = children.remove("attributeGroup", Some(NS_XSD)) {
+        super::attribute_group::parse_ref(child, parent, ctx)?;
+    }
+    let any_attribute = children
+        .remove("anyAttribute", Some(NS_XSD))
+        .map(|child| super::any::parse_attribute(child, ctx))
+        .transpose()?;
 
     children.prevent_unvisited_children()?;
 
@@ -49,6 +64,102 @@ where
             },
         )),
         is_virtual: false,
+        is_mixed: false,
+        any_attribute,
+        docs: None,
+    })
+}
+
+/// The restricted type repeats the attributes it changes, while all others (and the text of the
+/// base) are inherited once the base is known.
+fn parse_restriction<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<ElementDefinition, XsdError>
+where
+    'a: 'input,
+{
+    let base = node.try_attribute("base")?;
+    let base = ctx.get_type_name(base)?;
+    let prohibited = super::attribute::parse_prohibited(&node, ctx)?;
+
+    let mut children = node.children().namespace(NS_XSD).collect();
+
+    // read all attributes
+    let mut attributes = Vec::new();
+    while let Some(child) = children.remove("attribute", Some(NS_XSD)) {
+        if let Some(attr) = super::attribute::parse(child, parent, ctx)? {
+            attributes.push(attr);
+        }
+    }
+    while let Some(child) = children.remove("attributeGroup", Some(NS_XSD)) {
+        super::attribute_group::parse_ref(child, parent, ctx)?;
+    }
+    let any_attribute = children
+        .remove("anyAttribute", Some(NS_XSD))
+        .map(|child| super::any::parse_attribute(child, ctx))
+        .transpose()?;
+
+    let Facets {
+        restrictions,
+        enumerations,
+    } = super::simple_type::parse_facets(&node, &["attribute", "attributeGroup", "anyAttribute"])?;
+
+    let (content, facets) = if !enumerations.is_empty() {
+        // the enumerations replace the text type of the base
+        let enum_name = super::derive_virtual_name(
+            vec![parent, &ctx.get_node_name("Value", false)],
+            ctx,
+            false,
+        );
+        ctx.add_root(
+            enum_name.clone(),
+            Root::Enum(EnumDefinition {
+                variants: enumerations,
+                base: None,
+            }),
+        );
+        ctx.discover_type(&enum_name, Some(parent));
+        let content = ElementContent::Leaf(
+            enum_name.clone(),
+            LeafDefinition {
+                content: LeafContent::Named(enum_name),
+                restrictions: Vec::new(),
+                docs: None,
+            },
+        );
+        (Some(content), None)
+    } else if let LeafContent::Literal(literal) = &base {
+        let content = ElementContent::Leaf(
+            parent.clone(),
+            LeafDefinition {
+                content: LeafContent::Literal(literal.clone()),
+                restrictions,
+                docs: None,
+            },
+        );
+        (Some(content), None)
+    } else {
+        // the text is inherited from the base
+        (None, Some(restrictions))
+    };
+
+    if let LeafContent::Named(base) = base {
+        ctx.add_complex_restriction(ComplexRestriction {
+            element: parent.clone(),
+            base,
+            prohibited,
+            facets,
+        });
+    }
+
+    Ok(ElementDefinition {
+        attributes,
+        content,
+        is_virtual: false,
+        is_mixed: false,
+        any_attribute,
         docs: None,
     })
 }
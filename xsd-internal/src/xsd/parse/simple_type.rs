@@ -1,14 +1,17 @@
 use std::str::FromStr;
 
+use regex::Regex;
 use rust_decimal::Decimal;
 
-use crate::ast::{LeafContent, LeafDefinition, Name, Namespace, Restriction, Root};
+use crate::ast::{EnumDefinition, LeafContent, LeafDefinition, Name, Namespace, Restriction, Root};
+use crate::utils::translate_pattern;
 use crate::xsd::context::{Context, NS_XSD};
 use crate::xsd::error::XsdError;
 use crate::xsd::node::Node;
 
 pub fn parse<'a, 'input>(
     node: Node<'a, 'input>,
+    parent: &Name,
     ctx: &mut Context<'input>,
 ) -> Result<Root, XsdError>
 where
@@ -19,29 +22,110 @@ where
     let mut children = node.children().namespace(NS_XSD).collect();
     let docs = super::parse_annotation(children.remove("annotation", Some(NS_XSD)))?;
 
+    if let Some(union) = children.remove("union", Some(NS_XSD)) {
+        children.prevent_unvisited_children()?;
+        return Ok(super::union::parse(union, parent, ctx)?.with_docs(docs));
+    }
+    if let Some(list) = children.remove("list", Some(NS_XSD)) {
+        children.prevent_unvisited_children()?;
+        return Ok(super::list::parse(list, parent, ctx)?.with_docs(docs));
+    }
+
     let restriction = children.try_remove("restriction", Some(NS_XSD))?;
 
-    let attr = restriction.try_attribute("base")?;
-    let type_name = ctx.get_type_name(attr)?;
-    let type_ = match type_name {
-        LeafContent::Literal(type_) => type_,
-        LeafContent::Named(_) | LeafContent::Fixed(_) => {
-            return Err(XsdError::UnsupportedAttributeValue {
-                name: "base".to_string(),
-                value: attr.value().to_string(),
-                element: "restriction".to_string(),
-                range: attr.range(),
-            });
+    let base = if let Some(attr) = restriction.attribute("base") {
+        match ctx.get_type_name(attr)? {
+            LeafContent::Literal(type_) => Root::Leaf(LeafDefinition {
+                content: LeafContent::Literal(type_),
+                restrictions: Vec::new(),
+                docs: None,
+            }),
+            // the literal type and facets of the base are inherited during code generation
+            LeafContent::Named(name) => {
+                ctx.discover_type(&name, Some(parent));
+                Root::Leaf(LeafDefinition {
+                    content: LeafContent::Named(name),
+                    restrictions: Vec::new(),
+                    docs: None,
+                })
+            }
+            LeafContent::Fixed(_) | LeafContent::Wildcard(_) => {
+                return Err(XsdError::UnsupportedAttributeValue {
+                    name: "base".to_string(),
+                    value: attr.value().to_string(),
+                    element: "restriction".to_string(),
+                    range: attr.range(),
+                });
+            }
         }
+    } else {
+        // <restriction><simpleType>...</simpleType>...</restriction>
+        let child = restriction.try_child("simpleType", Some(NS_XSD))?;
+        parse(child, parent, ctx)?
     };
 
     children.prevent_unvisited_children()?;
 
+    let skip: &[&str] = if restriction.attribute("base").is_none() {
+        // the inline base type
+        &["simpleType"]
+    } else {
+        &[]
+    };
+    let Facets {
+        restrictions,
+        enumerations,
+    } = parse_facets(&restriction, skip)?;
+
+    match base {
+        Root::Leaf(mut def)
+            if matches!(def.content, LeafContent::Literal(_) | LeafContent::Named(_)) =>
+        {
+            Ok(if enumerations.is_empty() {
+                def.restrictions.extend(restrictions);
+                Root::Leaf(def).with_docs(docs)
+            } else {
+                Root::Enum(EnumDefinition {
+                    variants: enumerations,
+                    base: match def.content {
+                        LeafContent::Named(name) => Some(name),
+                        _ => None,
+                    },
+                })
+            })
+        }
+        // only the facets that apply to the items of a list are supported
+        Root::List(mut def)
+            if enumerations.is_empty()
+                && restrictions.iter().all(Restriction::applies_to_lists) =>
+        {
+            def.restrictions.extend(restrictions);
+            Ok(Root::List(def).with_docs(docs))
+        }
+        _ => Err(XsdError::UnsupportedElement {
+            name: "restriction".to_string(),
+            range: restriction.range(),
+        }),
+    }
+}
+
+/// The facets of a `<xs:restriction>`.
+#[derive(Debug, Default)]
+pub struct Facets {
+    pub restrictions: Vec<Restriction>,
+    pub enumerations: Vec<Name>,
+}
+
+/// Parses all facets of the given `<xs:restriction>`, except for the children in `skip` (which
+/// are handled by the caller).
+pub fn parse_facets(node: &Node<'_, '_>, skip: &[&str]) -> Result<Facets, XsdError> {
     let mut restrictions = Vec::new();
     let mut enumerations = Vec::new();
+    let mut patterns = Vec::new();
 
-    for child in restriction.children().namespace(NS_XSD).iter() {
+    for child in node.children().namespace(NS_XSD).iter() {
         match child.name() {
+            name if skip.contains(&name) => {}
             "enumeration" => {
                 enumerations.push(Name::new(
                     child.try_attribute("value")?.value().into_owned(),
@@ -72,9 +156,18 @@ where
                 })?;
                 restrictions.push(Restriction::MaxLength(value));
             }
-            "pattern" => restrictions.push(Restriction::Pattern(
-                child.try_attribute("value")?.value().into_owned(),
-            )),
+            "pattern" => {
+                let attr = child.try_attribute("value")?;
+                let pattern = attr.value().into_owned();
+                if let Err(err) = Regex::new(&translate_pattern(&pattern)) {
+                    return Err(XsdError::InvalidPattern {
+                        pattern,
+                        err,
+                        range: attr.range(),
+                    });
+                }
+                patterns.push(pattern);
+            }
             "minInclusive" => {
                 let attr = child.try_attribute("value")?;
                 let value =
@@ -118,13 +211,21 @@ where
         }
     }
 
-    Ok(if enumerations.is_empty() {
-        Root::Leaf(LeafDefinition {
-            content: LeafContent::Literal(type_),
-            restrictions,
-            docs,
-        })
-    } else {
-        Root::Enum(enumerations)
+    // multiple patterns of the same restriction step are alternatives
+    match patterns.len() {
+        0 => {}
+        1 => restrictions.extend(patterns.into_iter().map(Restriction::Pattern)),
+        _ => restrictions.push(Restriction::Pattern(
+            patterns
+                .iter()
+                .map(|p| format!("({})", p))
+                .collect::<Vec<_>>()
+                .join("|"),
+        )),
+    }
+
+    Ok(Facets {
+        restrictions,
+        enumerations,
     })
 }
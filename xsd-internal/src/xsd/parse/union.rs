@@ -0,0 +1,77 @@
+use inflector::Inflector;
+
+use crate::ast::{LeafContent, Name, Root, UnionDefinition};
+use crate::xsd::context::{Context, NS_XSD};
+use crate::xsd::error::XsdError;
+use crate::xsd::node::Node;
+
+pub fn parse<'a, 'input>(
+    node: Node<'a, 'input>,
+    parent: &Name,
+    ctx: &mut Context<'input>,
+) -> Result<Root, XsdError>
+where
+    'a: 'input,
+{
+    let mut variants = Vec::new();
+
+    // <union memberTypes="xs:date MyType" />
+    if let Some(attr) = node.attribute("memberTypes") {
+        for member in attr.value().split_whitespace() {
+            let content = ctx.resolve_type_name(member, attr.range())?;
+            let name = match &content {
+                LeafContent::Literal(literal) => literal.xsd_name().to_pascal_case(),
+                LeafContent::Named(name) => {
+                    ctx.discover_type(name, Some(parent));
+                    name.name.clone()
+                }
+                LeafContent::Fixed(_) | LeafContent::Wildcard(_) => unreachable!(),
+            };
+            variants.push((ctx.get_node_name(&name, false), content));
+        }
+    }
+    node.prevent_unvisited_attributes()?;
+
+    // <union><simpleType>...</simpleType></union>
+    let mut children = node.children().namespace(NS_XSD).collect();
+    // TODO: docs of union members have nowhere to go
+    super::parse_annotation(children.remove("annotation", Some(NS_XSD)))?;
+    while let Some(child) = children.remove("simpleType", Some(NS_XSD)) {
+        let variant_name = ctx.get_node_name(&format!("Variant{}", variants.len() + 1), false);
+        let virtual_name = super::derive_virtual_name(vec![parent, &variant_name], ctx, false);
+        let variant = match super::parse_inline_simple_type(child, &virtual_name, parent, ctx)? {
+            LeafContent::Literal(content) => (
+                ctx.get_node_name(&content.xsd_name().to_pascal_case(), false),
+                LeafContent::Literal(content),
+            ),
+            content => (variant_name, content),
+        };
+        variants.push(variant);
+    }
+    children.prevent_unvisited_children()?;
+
+    // variants are named after their types, which can repeat (e.g. the same name in different
+    // namespaces), so repeated names get the position of the variant as a suffix
+    for i in 1..variants.len() {
+        let ident = variants[i].0.name.to_pascal_case();
+        if variants[..i]
+            .iter()
+            .any(|(name, _)| name.name.to_pascal_case() == ident)
+        {
+            variants[i].0.name = format!("{}{}", ident, i + 1);
+        }
+    }
+
+    if variants.is_empty() {
+        return Err(XsdError::MissingElement {
+            name: "simpleType".to_string(),
+            parent: node.name().to_string(),
+            range: node.range(),
+        });
+    }
+
+    Ok(Root::Union(UnionDefinition {
+        variants,
+        docs: None,
+    }))
+}
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use roxmltree::{Document, Node};
+
+use super::schema::SchemaError;
+
+pub const NS_CATALOG: &str = "urn:oasis:names:tc:entity:xmlns:xml:catalog";
+const NS_XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Loads the schemas referenced by `<xs:include>`, `<xs:import>`, `<xs:redefine>` and
+/// `<xs:override>`.
+pub trait SchemaResolver {
+    /// Resolves the `schemaLocation` (and the `namespace` of imports) of a schema referenced from
+    /// a schema in the directory `base`. Returns `None` if the schema cannot be found.
+    fn resolve(
+        &self,
+        location: Option<&str>,
+        namespace: Option<&str>,
+        base: &Path,
+    ) -> io::Result<Option<ResolvedSchema>>;
+}
+
+#[derive(Debug)]
+pub struct ResolvedSchema {
+    /// Identifies the schema, locations inside of it are relative to its parent directory.
+    pub path: PathBuf,
+    pub data: String,
+}
+
+/// Reads schemas from the file system, with relative locations being joined onto the directory of
+/// the referencing schema. Absolute URLs (and namespaces) are only resolved via XML catalogs.
+#[derive(Debug, Default)]
+pub struct DefaultResolver {
+    catalogs: Vec<Catalog>,
+    /// The schema files of imports without a `schemaLocation` by their namespace.
+    namespaces: HashMap<String, PathBuf>,
+}
+
+impl DefaultResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an OASIS XML catalog, catalogs are consulted in the order they were added.
+    pub fn with_catalog(mut self, path: impl AsRef<Path>) -> Result<Self, SchemaError> {
+        self.catalogs.push(Catalog::load(path)?);
+        Ok(self)
+    }
+
+    /// Resolves imports of `namespace` without a `schemaLocation` to the schema file at `path`.
+    pub fn with_namespace(
+        mut self,
+        namespace: impl Into<String>,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        self.namespaces.insert(namespace.into(), path.into());
+        self
+    }
+
+    fn lookup(&self, uri: &str) -> Option<PathBuf> {
+        self.catalogs.iter().find_map(|catalog| catalog.lookup(uri))
+    }
+}
+
+impl SchemaResolver for DefaultResolver {
+    fn resolve(
+        &self,
+        location: Option<&str>,
+        namespace: Option<&str>,
+        base: &Path,
+    ) -> io::Result<Option<ResolvedSchema>> {
+        let path = location
+            .and_then(|location| self.lookup(location))
+            .or_else(|| {
+                let namespace = namespace.filter(|_| location.is_none())?;
+                self.namespaces.get(namespace).cloned()
+            })
+            .or_else(|| namespace.and_then(|namespace| self.lookup(namespace)))
+            .or_else(|| {
+                let location = location?;
+                match location.strip_prefix("file://") {
+                    Some(path) => Some(PathBuf::from(path)),
+                    None if location.contains("://") => None,
+                    None => Some(base.join(location)),
+                }
+            });
+
+        match path {
+            Some(path) => Ok(Some(ResolvedSchema {
+                data: read_to_string(&path)?,
+                path,
+            })),
+            None => Ok(None),
+        }
+    }
+}
+
+/// An [OASIS XML catalog](https://www.oasis-open.org/committees/download.php/14809/xml-catalogs.html)
+/// mapping URIs (e.g. `http://...` schema locations or namespaces) to local files.
+///
+/// Supports `uri`, `system`, `rewriteURI`, `rewriteSystem`, `group` and `nextCatalog` entries.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    entries: Vec<CatalogEntry>,
+    /// Consulted if none of the entries match.
+    next: Vec<Catalog>,
+}
+
+#[derive(Debug)]
+enum CatalogEntry {
+    /// `<uri>` or `<system>`
+    Exact { uri: String, path: PathBuf },
+    /// `<rewriteURI>` or `<rewriteSystem>`
+    Rewrite { prefix: String, path: PathBuf },
+}
+
+impl Catalog {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SchemaError> {
+        Self::load_with_visited(path.as_ref(), &mut HashSet::new())
+    }
+
+    fn load_with_visited(path: &Path, visited: &mut HashSet<PathBuf>) -> Result<Self, SchemaError> {
+        let error = |message: String| SchemaError::Catalog {
+            file: path.display().to_string(),
+            message,
+        };
+
+        let data = read_to_string(path).map_err(|err| error(err.to_string()))?;
+        let doc = Document::parse(&data).map_err(|err| error(err.to_string()))?;
+        let root = doc.root_element();
+        if root.tag_name().namespace() != Some(NS_CATALOG) || root.tag_name().name() != "catalog" {
+            return Err(error("expected a root `catalog` element".to_string()));
+        }
+
+        visited.insert(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+
+        let mut catalog = Catalog::default();
+        let mut next = Vec::new();
+        let base = path.parent().unwrap_or(Path::new(""));
+        catalog
+            .parse_entries(root, base, &mut next)
+            .map_err(error)?;
+
+        for path in next {
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if !visited.contains(&canonical) {
+                catalog.next.push(Self::load_with_visited(&path, visited)?);
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    /// Parses the entries of a `<catalog>` or `<group>`, `base` is the directory relative paths
+    /// are resolved against.
+    fn parse_entries(
+        &mut self,
+        node: Node<'_, '_>,
+        base: &Path,
+        next: &mut Vec<PathBuf>,
+    ) -> Result<(), String> {
+        let base = with_xml_base(node, base);
+        for child in node.children().filter(|child| child.is_element()) {
+            if child.tag_name().namespace() != Some(NS_CATALOG) {
+                continue;
+            }
+
+            let attribute = |name: &str| {
+                child.attribute(name).ok_or_else(|| {
+                    format!(
+                        "missing attribute `{name}` in `{}`",
+                        child.tag_name().name()
+                    )
+                })
+            };
+            let entry_base = with_xml_base(child, &base);
+            match child.tag_name().name() {
+                "uri" | "system" => {
+                    let uri = match child.tag_name().name() {
+                        "uri" => attribute("name")?,
+                        _ => attribute("systemId")?,
+                    };
+                    self.entries.push(CatalogEntry::Exact {
+                        uri: uri.to_string(),
+                        path: entry_base.join(attribute("uri")?),
+                    });
+                }
+                "rewriteURI" | "rewriteSystem" => {
+                    let prefix = match child.tag_name().name() {
+                        "rewriteURI" => attribute("uriStartString")?,
+                        _ => attribute("systemIdStartString")?,
+                    };
+                    self.entries.push(CatalogEntry::Rewrite {
+                        prefix: prefix.to_string(),
+                        path: entry_base.join(attribute("rewritePrefix")?),
+                    });
+                }
+                "group" => self.parse_entries(child, &base, next)?,
+                "nextCatalog" => next.push(entry_base.join(attribute("catalog")?)),
+                // entries for public identifiers or delegation don't apply to schema locations
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The local file the given URI is mapped to. Exact matches take precedence over rewrites,
+    /// of which the one with the longest matching prefix is used.
+    pub fn lookup(&self, uri: &str) -> Option<PathBuf> {
+        let exact = self.entries.iter().find_map(|entry| match entry {
+            CatalogEntry::Exact { uri: name, path } if name == uri => Some(path.clone()),
+            _ => None,
+        });
+        if exact.is_some() {
+            return exact;
+        }
+
+        let rewrite = self
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                CatalogEntry::Rewrite { prefix, path } => uri
+                    .strip_prefix(prefix.as_str())
+                    .map(|rest| (prefix, path, rest)),
+                _ => None,
+            })
+            .max_by_key(|(prefix, _, _)| prefix.len());
+        if let Some((_, path, rest)) = rewrite {
+            // the prefix is replaced as is (it doesn't need to end at a path separator)
+            let mut path = path.clone().into_os_string();
+            path.push(rest);
+            return Some(path.into());
+        }
+
+        self.next.iter().find_map(|catalog| catalog.lookup(uri))
+    }
+}
+
+fn with_xml_base(node: Node<'_, '_>, base: &Path) -> PathBuf {
+    match node.attribute((NS_XML, "base")) {
+        Some(xml_base) => base.join(xml_base),
+        None => base.to_path_buf(),
+    }
+}
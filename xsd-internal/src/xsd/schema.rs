@@ -1,13 +1,20 @@
 use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::read_to_string;
 use std::io;
 use std::ops::Range;
-use std::{collections::HashMap, path::Path};
+use std::path::{Path, PathBuf};
 
-use super::context::{Context, SchemaContext, SharedContext, NS_XSD};
+use super::context::{
+    Context, Options, PendingReferences, SchemaContext, SchemaFiles, SharedContext, NS_XSD,
+};
 use super::error::XsdError;
 use super::node::Node;
-use crate::ast::{Name, Namespace, Root};
+use super::resolver::{DefaultResolver, ResolvedSchema, SchemaResolver};
+use crate::ast::{
+    DerivedDefinition, ElementContent, ElementDefinition, IdentityConstraint, IdentityKind,
+    LeafContent, Name, Namespace, Root,
+};
 use crate::utils::escape_ident;
 use inflector::Inflector;
 use proc_macro2::TokenStream;
@@ -18,16 +25,19 @@ use roxmltree::{Document, TextPos};
 pub struct Schema {
     pub(crate) dependencies: HashMap<Name, HashSet<Name>>,
     pub(crate) context: SchemaContext,
+    pub(crate) pending: PendingReferences,
+    pub(crate) files: SchemaFiles,
 }
 
 impl Schema {
     pub fn parse_file(path: impl AsRef<Path>) -> Result<Self, SchemaError> {
-        Self::parse_file_with_context(path, None)
+        Self::parse_file_with_resolver(path, &DefaultResolver::new())
     }
 
-    fn parse_file_with_context(
+    /// Parses the schema file, with the referenced schemas being loaded by `resolver`.
+    pub fn parse_file_with_resolver(
         path: impl AsRef<Path>,
-        shared: Option<SharedContext>,
+        resolver: &dyn SchemaResolver,
     ) -> Result<Self, SchemaError> {
         let path = path.as_ref();
 
@@ -41,17 +51,49 @@ impl Schema {
             }
         };
 
-        Schema::parse_with_context(&data, path, shared)
+        Schema::parse_with_context(&data, path, resolver, None, Namespace::None, &[])
+    }
+
+    /// Parses multiple schema files into one schema, with each file being loaded only once (even
+    /// if also imported by another one). The first file is the primary schema, whose target
+    /// namespace is the default namespace of the generated XML.
+    pub fn parse_files_with_resolver(
+        paths: &[PathBuf],
+        resolver: &dyn SchemaResolver,
+    ) -> Result<Self, SchemaError> {
+        let Some((path, others)) = paths.split_first() else {
+            return Err(SchemaError::NoSchema);
+        };
+        let data = read_to_string(path).map_err(|err| SchemaError::Open {
+            err,
+            file: path.to_string_lossy().to_string(),
+        })?;
+
+        Schema::parse_with_context(&data, path, resolver, None, Namespace::None, others)
     }
 
     pub fn parse(data: &str, path: impl AsRef<Path>) -> Result<Self, SchemaError> {
-        Self::parse_with_context(data, path, None)
+        Self::parse_with_resolver(data, path, &DefaultResolver::new())
+    }
+
+    pub fn parse_with_resolver(
+        data: &str,
+        path: impl AsRef<Path>,
+        resolver: &dyn SchemaResolver,
+    ) -> Result<Self, SchemaError> {
+        Self::parse_with_context(data, path, resolver, None, Namespace::None, &[])
     }
 
+    /// `chameleon_namespace` is the namespace adopted by a schema without a `targetNamespace`
+    /// (when it is included into a schema with one). `others` are further root schema files,
+    /// loaded by the outermost schema.
     fn parse_with_context(
         data: &str,
         path: impl AsRef<Path>,
+        resolver: &dyn SchemaResolver,
         shared: Option<SharedContext>,
+        chameleon_namespace: Namespace,
+        others: &[PathBuf],
     ) -> Result<Self, SchemaError> {
         let path = path.as_ref().to_path_buf();
         let doc = match Document::parse(data) {
@@ -69,7 +111,16 @@ impl Schema {
 
         let base_path = path.parent().unwrap_or(path.as_path());
         let root_node = doc.root_element().into();
-        Schema::parse_schema_with_context(&root_node, base_path, shared).map_err(|err| match err {
+        Schema::parse_schema_with_context(
+            &root_node,
+            base_path,
+            Some(&path),
+            resolver,
+            shared,
+            chameleon_namespace,
+            others,
+        )
+        .map_err(|err| match err {
             ParseError::Schema(err) => err,
             ParseError::Xsd(err) => {
                 let pos = err
@@ -87,13 +138,25 @@ impl Schema {
     }
 
     pub fn parse_schema(root: &Node<'_, '_>, base_path: &Path) -> Result<Self, ParseError> {
-        Self::parse_schema_with_context(root, base_path, None)
+        Self::parse_schema_with_context(
+            root,
+            base_path,
+            None,
+            &DefaultResolver::new(),
+            None,
+            Namespace::None,
+            &[],
+        )
     }
 
     fn parse_schema_with_context(
         root: &Node<'_, '_>,
         base_path: &Path,
+        file: Option<&Path>,
+        resolver: &dyn SchemaResolver,
         shared: Option<SharedContext>,
+        chameleon_namespace: Namespace,
+        others: &[PathBuf],
     ) -> Result<Self, ParseError> {
         if root.namespace().as_deref() != Some(NS_XSD) || root.name() != "schema" {
             return Err(XsdError::UnsupportedElement {
@@ -104,44 +167,247 @@ impl Schema {
         }
 
         let target_namespace = root.attribute("targetNamespace").map(|a| a.value());
+        // only the outermost schema resolves references that might span multiple files
+        let is_outermost = shared.is_none();
 
         let mut ctx = Context::new(
             root,
             target_namespace.as_deref(),
             shared.unwrap_or_default(),
         );
+        if target_namespace.is_none() {
+            ctx.adopt_namespace(chameleon_namespace);
+        }
+
+        let file = file.map(canonicalize);
+        if let Some(file) = &file {
+            let target_namespace = ctx.target_namespace();
+            let files = ctx.files();
+            files.loaded.insert((file.clone(), target_namespace));
+            files.chain.push(file.clone());
+        }
 
         for child in root.children().namespace(NS_XSD).iter() {
-            // TODO: prevent circular includes
-            if child.name() == "include" || child.name() == "import" {
-                // TODO: make sure that it is a relative path
-                let location: &str = &child.try_attribute("schemaLocation")?.value();
-                let mut path = base_path.to_path_buf();
-                path.push(location);
-
-                // merge imports
-                let mut schema = Schema::parse_file_with_context(path, Some(ctx.take_shared()))?;
-                for (name, root) in std::mem::take(&mut schema.context.elements) {
-                    ctx.add_root(name, root);
+            if matches!(child.name(), "include" | "import" | "redefine" | "override") {
+                let namespace = match child.name() {
+                    "import" => child.attribute("namespace").map(|a| a.value()),
+                    _ => None,
+                };
+                let location = match (child.attribute("schemaLocation"), &namespace) {
+                    (Some(location), _) => location.value(),
+                    // imports without a location are resolved once all other schemas are loaded,
+                    // as one of them might provide the namespace
+                    (None, Some(namespace)) => {
+                        let import = (namespace.to_string(), base_path.to_path_buf());
+                        ctx.files().namespace_imports.push(import);
+                        continue;
+                    }
+                    (None, None) => child.try_attribute("schemaLocation")?.value(),
+                };
+                let resolved =
+                    match resolver.resolve(Some(&location), namespace.as_deref(), base_path) {
+                        Ok(Some(resolved)) => resolved,
+                        Ok(None) => {
+                            return Err(SchemaError::Unresolved {
+                                location: location.into_owned(),
+                            }
+                            .into())
+                        }
+                        Err(err) => {
+                            return Err(SchemaError::Open {
+                                err,
+                                file: location.into_owned(),
+                            }
+                            .into())
+                        }
+                    };
+                if !load_schema(&mut ctx, child.name(), &resolved, resolver)? {
+                    continue;
+                }
+
+                // the components that replace the ones of the loaded schema
+                let keep_original = child.name() == "redefine";
+                for component in child.children().namespace(NS_XSD).iter() {
+                    if component.name() == "annotation" {
+                        continue;
+                    }
+                    let name = component_name(&component, &ctx)?;
+                    ctx.replace_component(&name, keep_original, |ctx| {
+                        parse_component(component, name.clone(), ctx)
+                    })?;
                 }
-                ctx.set_shared(schema.into_shared());
 
                 continue;
             }
 
-            let name = Name::new(child.try_attribute("name")?.value(), ctx.target_namespace());
+            let name = component_name(&child, &ctx)?;
+            parse_component(child, name, &mut ctx)?;
+        }
+
+        if is_outermost {
+            for path in others {
+                let data = read_to_string(path).map_err(|err| SchemaError::Open {
+                    err,
+                    file: path.to_string_lossy().to_string(),
+                })?;
+                let resolved = ResolvedSchema {
+                    path: path.clone(),
+                    data,
+                };
+                load_schema(&mut ctx, "import", &resolved, resolver)?;
+            }
+
+            // loading these schemas might add further imports without a location
+            loop {
+                let imports = std::mem::take(&mut ctx.files().namespace_imports);
+                if imports.is_empty() {
+                    break;
+                }
+                for (namespace, base_path) in imports {
+                    if ctx.is_namespace_loaded(&namespace) {
+                        continue;
+                    }
+                    let resolved = match resolver.resolve(None, Some(&namespace), &base_path) {
+                        Ok(Some(resolved)) => resolved,
+                        Ok(None) => {
+                            return Err(SchemaError::UnresolvedNamespace { namespace }.into())
+                        }
+                        Err(err) => {
+                            return Err(SchemaError::Open {
+                                err,
+                                file: namespace,
+                            }
+                            .into())
+                        }
+                    };
+                    load_schema(&mut ctx, "import", &resolved, resolver)?;
+                }
+            }
 
-            let root = crate::xsd::parse::root::parse(child, &name, &mut ctx)?;
-            ctx.add_root(name, root);
+            ctx.check_circular_types()?;
+            ctx.resolve_group_names();
+            ctx.resolve_attribute_groups()?;
+            ctx.resolve_complex_restrictions()?;
+            ctx.resolve_list_restrictions()?;
+            ctx.resolve_nillable_elements();
+            ctx.resolve_substitution_groups()?;
+            ctx.resolve_identity_constraints()?;
+        }
+        if file.is_some() {
+            ctx.files().chain.pop();
         }
 
         Ok(ctx.into_schema())
     }
 
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.context.options = options;
+        if self.context.options.xsi_type {
+            self.resolve_derived_types();
+        }
+        self.context.assign_modules();
+        self
+    }
+
+    /// Replaces all usages of complex types that have derived types by a virtual enum over the
+    /// base and the derived types, which are selected by `xsi:type` (see [Options::xsi_type]).
+    fn resolve_derived_types(&mut self) {
+        let derivations = std::mem::take(&mut self.pending.derived_types);
+
+        let mut enums = HashMap::new();
+        let mut dependencies = Vec::new();
+        for (base, _) in &derivations {
+            // simple types (e.g. the base of a `<xs:simpleContent>` extension) can't be replaced
+            if enums.contains_key(base)
+                || !matches!(self.context.elements.get(base), Some(Root::Element(_)))
+            {
+                continue;
+            }
+
+            // types derived from derived types are also derived from the base
+            let mut derived: Vec<Name> = Vec::new();
+            let mut pending = vec![base.clone()];
+            while let Some(name) = pending.pop() {
+                for (_, child) in derivations.iter().filter(|(base, _)| *base == name) {
+                    if child != base && !derived.contains(child) {
+                        derived.push(child.clone());
+                        pending.push(child.clone());
+                    }
+                }
+            }
+
+            let taken = |name: &Name| {
+                self.context
+                    .elements
+                    .keys()
+                    .chain(enums.values().map(|(name, _)| name))
+                    .any(|other| {
+                        other.namespace == name.namespace
+                            && other.name.to_pascal_case() == name.name.to_pascal_case()
+                    })
+            };
+            let mut name = Name::new(format!("{}Derived", base.name), base.namespace);
+            let mut n = 1;
+            while taken(&name) {
+                n += 1;
+                name = Name::new(format!("{}Derived{}", base.name, n), base.namespace);
+            }
+
+            let def = DerivedDefinition {
+                base: base.clone(),
+                derived,
+            };
+            for ty in def.types() {
+                dependencies.push((name.clone(), ty.clone()));
+            }
+            enums.insert(base.clone(), (name, def));
+        }
+
+        // usages of the base types become usages of the enums (except for the content of derived
+        // types, which is always the base itself)
+        for (parent, root) in &mut self.context.elements {
+            let definitions = match root {
+                Root::Element(ElementDefinition {
+                    content: Some(ElementContent::Leaves(leaves)),
+                    ..
+                }) => leaves
+                    .iter_mut()
+                    .filter(|leaf| !leaf.is_virtual)
+                    .map(|leaf| &mut leaf.definition)
+                    .collect(),
+                Root::Choice(def) => def
+                    .variants
+                    .iter_mut()
+                    .filter(|leaf| !leaf.is_virtual)
+                    .map(|leaf| &mut leaf.definition)
+                    .collect(),
+                Root::Leaf(def) => vec![def],
+                _ => continue,
+            };
+            for def in definitions {
+                if let LeafContent::Named(name) = &def.content {
+                    if let Some((derived, _)) = enums.get(name) {
+                        def.content = LeafContent::Named(derived.clone());
+                        dependencies.push((parent.clone(), derived.clone()));
+                    }
+                }
+            }
+        }
+        for (parent, name) in dependencies {
+            self.dependencies.entry(parent).or_default().insert(name);
+        }
+
+        for (name, def) in enums.into_values() {
+            self.context.elements.insert(name, Root::Derived(def));
+        }
+    }
+
     fn into_shared(self) -> SharedContext {
         SharedContext {
             namespaces: self.context.namespaces,
             dependencies: self.dependencies,
+            pending: self.pending,
+            files: self.files,
         }
     }
 
@@ -149,23 +415,68 @@ impl Schema {
         self.context.elements.iter()
     }
 
+    /// Generates all types, with the types of each target namespace being placed in a submodule
+    /// (which is re-exported, so types can also be used without the submodule path).
     pub fn generate_all(&self) -> Result<TokenStream, SchemaError> {
-        let mut result = TokenStream::new();
-
-        for name in self.context.elements.keys() {
-            result.append_all(self.generate_element(name)?);
-        }
-
-        Ok(result)
+        self.generate_modules(self.context.elements.keys())
     }
 
     pub fn generate_element_and_dependencies<'a>(
         &'a self,
         name: &'a Name,
         already_generated: &mut HashSet<&'a Name>,
+    ) -> Result<TokenStream, SchemaError> {
+        let mut names = Vec::new();
+        for name in self.dependencies_of(name) {
+            if already_generated.insert(name) {
+                names.push(name);
+            }
+        }
+
+        self.generate_modules(names)
+    }
+
+    fn generate_modules<'a>(
+        &'a self,
+        names: impl IntoIterator<Item = &'a Name>,
     ) -> Result<TokenStream, SchemaError> {
         let mut result = TokenStream::new();
+        let mut modules: BTreeMap<&str, TokenStream> = BTreeMap::new();
+
+        for name in names {
+            let element = self.generate_element(name)?;
+            match self.module_of(name) {
+                Some(module) => modules.entry(module).or_default().append_all(element),
+                None => result.append_all(element),
+            }
+        }
+
+        for (module, items) in modules {
+            let module = escape_ident(module);
+            result.append_all(quote! {
+                pub mod #module {
+                    #[allow(unused_imports)]
+                    use super::*;
+
+                    #items
+                }
+                #[allow(unused_imports, ambiguous_glob_reexports)]
+                pub use #module::*;
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn module_of(&self, name: &Name) -> Option<&str> {
+        match name.namespace {
+            Namespace::Id(id) => self.context.modules.get(&id).map(String::as_str),
+            Namespace::None => None,
+        }
+    }
 
+    /// The given root and all roots it (transitively) depends on.
+    fn dependencies_of<'a>(&'a self, name: &'a Name) -> HashSet<&'a Name> {
         let mut names = HashSet::with_capacity(1);
         let mut new_names = HashSet::with_capacity(1);
         new_names.insert(name);
@@ -190,15 +501,39 @@ impl Schema {
             new_names = next_names;
         }
 
-        for name in names {
-            if already_generated.contains(name) {
-                continue;
+        names
+    }
+
+    /// The identity constraints of all elements that can be part of the given root, including the
+    /// keys referenced by their keyrefs.
+    fn identity_constraints_of(&self, name: &Name) -> Vec<&IdentityConstraint> {
+        let mut constraints = self
+            .dependencies_of(name)
+            .into_iter()
+            .filter_map(|name| self.context.identity_constraints.get(name))
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let mut i = 0;
+        while let Some(constraint) = constraints.get(i) {
+            if let IdentityKind::KeyRef { refer } = &constraint.kind {
+                if !constraints.iter().any(|c| &c.name == refer) {
+                    if let Some(key) = self
+                        .context
+                        .identity_constraints
+                        .values()
+                        .flatten()
+                        .find(|c| &c.name == refer)
+                    {
+                        constraints.push(key);
+                    }
+                }
             }
-            result.append_all(self.generate_element(name)?);
-            already_generated.insert(name);
+            i += 1;
         }
 
-        Ok(result)
+        constraints.sort_by(|a, b| a.name.name.cmp(&b.name.name));
+        constraints
     }
 
     fn generate_element(&self, name: &Name) -> Result<TokenStream, SchemaError> {
@@ -212,7 +547,12 @@ impl Schema {
 
         let mut result = TokenStream::new();
 
-        // TODO: handle duplicates with different prefixes
+        // the helpers are used by the types of the other namespace submodules, too
+        let helper_vis = match self.module_of(name) {
+            Some(_) => quote!(pub(super)),
+            None => TokenStream::new(),
+        };
+
         let name_ident = escape_ident(&name.name.to_pascal_case());
         let kind = if el.is_enum() {
             quote!(enum)
@@ -235,27 +575,38 @@ impl Schema {
 
         let to_xml = el.to_xml_impl(&self.context);
 
-        let name_xml = self.context.get_xml_element_name(name);
+        // the namespace of the root is the default namespace of the document (if its local
+        // elements are in it, too)
+        let default_ns = match name.namespace {
+            Namespace::Id(id) if self.context.namespaces.get_by_id(id).is_qualified => Some(id),
+            _ => None,
+        };
+        // the prefixes are declared once at the root for all namespaces of the types it can
+        // contain (other elements declare their prefix themselves)
+        let used_ns = self
+            .dependencies_of(name)
+            .into_iter()
+            .map(|name| name.namespace)
+            .collect::<HashSet<_>>();
+        let mut name_xml = self.context.get_xml_name(name);
         let mut element_ns = Vec::new();
-        if self.context.is_qualified {
-            if let Namespace::Id(id) = self.context.target_namespace {
-                let ns = self.context.namespaces.get_by_id(id);
-                let namespace = &ns.namespace;
-                element_ns.push(quote! { .set_default_ns(#namespace) });
-            }
-        }
         for (id, ns) in self.context.namespaces.iter() {
-            if self.context.is_qualified && Namespace::Id(id) == self.context.target_namespace {
+            if !used_ns.contains(&Namespace::Id(id)) {
                 continue;
             }
-            let prefix = &ns.prefix;
             let namespace = &ns.namespace;
-            element_ns.push(quote! { .set_ns(#prefix, #namespace) });
+            if default_ns == Some(id) {
+                name_xml = name.name.clone();
+                element_ns.push(quote! { .set_default_ns(#namespace) });
+            } else {
+                let prefix = &ns.prefix;
+                element_ns.push(quote! { .set_ns(#prefix, #namespace) });
+            }
         }
 
         result.append_all(quote! {
             impl #name_ident {
-                pub fn to_xml(&self) -> Result<Vec<u8>, ::xsd::xml::writer::Error> {
+                pub fn to_xml(&self) -> Result<Vec<u8>, ::xsd::encode::ToXmlError> {
                     use ::xsd::xml::writer::events::XmlEvent;
 
                     let mut body = Vec::new();
@@ -269,19 +620,19 @@ impl Schema {
                         standalone: None,
                     })?;
                     let mut ctx = ::xsd::Context::new(#name_xml);
+                    #(ctx #element_ns;)*
                     self.to_xml_writer(ctx, &mut writer)?;
 
                     Ok(body)
                 }
 
-                fn to_xml_writer<'a, 'b, W: ::std::io::Write>(
+                #helper_vis fn to_xml_writer<'a, 'b, W: ::std::io::Write>(
                     &'a self,
                     mut ctx: ::xsd::Context<'a, 'b>,
                     writer: &mut ::xsd::xml::writer::EventWriter<W>,
-                ) -> Result<(), ::xsd::xml::writer::Error> {
+                ) -> Result<(), ::xsd::encode::ToXmlError> {
                     use ::xsd::xml::writer::events::XmlEvent;
 
-                    #(ctx #element_ns;)*
                     #to_xml
 
                     Ok(())
@@ -301,7 +652,7 @@ impl Schema {
                     Self::from_xml_node(&node)
                 }
 
-                fn from_xml_node(node: &::xsd::decode::Node) -> Result<Self, ::xsd::decode::FromXmlError> {
+                #helper_vis fn from_xml_node(node: &::xsd::decode::Node) -> Result<Self, ::xsd::decode::FromXmlError> {
                     Ok(#from_xml)
                 }
             }
@@ -311,12 +662,28 @@ impl Schema {
 
         result.append_all(quote! {
             impl #name_ident {
-                fn lookahead(node: &::xsd::decode::Node) -> bool {
+                #helper_vis fn lookahead(node: &::xsd::decode::Node) -> bool {
                     #lookahead
                 }
             }
         });
 
+        let constraints = self.identity_constraints_of(name);
+        if !constraints.is_empty() {
+            let constraints = constraints.iter().map(|c| c.to_impl(&self.context));
+            result.append_all(quote! {
+                impl #name_ident {
+                    /// Validates the `<xs:key>`, `<xs:keyref>` and `<xs:unique>` constraints of
+                    /// the document.
+                    pub fn validate_identity(&self) -> Result<(), ::xsd::identity::IdentityError> {
+                        const CONSTRAINTS: &[::xsd::identity::IdentityConstraint] = &[#(#constraints),*];
+                        let xml = self.to_xml()?;
+                        ::xsd::identity::validate(&String::from_utf8_lossy(&xml), CONSTRAINTS)
+                    }
+                }
+            });
+        }
+
         // eprintln!("{}", result.to_string());
         Ok(result)
     }
@@ -326,6 +693,102 @@ impl Schema {
     }
 }
 
+/// The canonical form of `path` to detect repeated loads of the same file (or the path itself if
+/// the file does not exist, which fails once the file is read).
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Loads the schema referenced by an `<xs:{kind}>` and adds its components to `ctx`. Returns
+/// `false` if the schema was skipped, as it was already loaded.
+fn load_schema(
+    ctx: &mut Context<'_>,
+    kind: &str,
+    resolved: &ResolvedSchema,
+    resolver: &dyn SchemaResolver,
+) -> Result<bool, ParseError> {
+    let path = canonicalize(&resolved.path);
+    let target_namespace = ctx.target_namespace();
+    let files = ctx.files();
+    if let Some(i) = files.chain.iter().position(|file| *file == path) {
+        // the components of mutually importing schemas are merged once the outer schema is done
+        if kind == "import" {
+            return Ok(false);
+        }
+        return Err(SchemaError::CircularInclude {
+            chain: files.chain[i..]
+                .iter()
+                .chain(std::iter::once(&path))
+                .map(|file| file.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> "),
+        }
+        .into());
+    }
+    // every schema is only loaded once (redefinitions need a fresh copy though)
+    let is_loaded = match kind {
+        "include" => files.loaded.contains(&(path.clone(), target_namespace)),
+        "import" => files.loaded.iter().any(|(file, _)| *file == path),
+        _ => false,
+    };
+    if is_loaded {
+        return Ok(false);
+    }
+
+    // components of included schemas without a target namespace are added to ours
+    let chameleon_namespace = match kind {
+        "import" => Namespace::None,
+        _ => ctx.target_namespace(),
+    };
+
+    // merge imports
+    let mut schema = Schema::parse_with_context(
+        &resolved.data,
+        &resolved.path,
+        resolver,
+        Some(ctx.take_shared()),
+        chameleon_namespace,
+        &[],
+    )?;
+    for (name, root) in std::mem::take(&mut schema.context.elements) {
+        ctx.add_root(name, root);
+    }
+    ctx.set_shared(schema.into_shared());
+
+    Ok(true)
+}
+
+/// The name of a top-level component (groups are named apart from the types, see
+/// [crate::xsd::parse::group::group_name]).
+fn component_name(node: &Node<'_, '_>, ctx: &Context<'_>) -> Result<Name, XsdError> {
+    let name = Name::new(node.try_attribute("name")?.value(), ctx.target_namespace());
+    if node.name() == "group" {
+        Ok(crate::xsd::parse::group::group_name(&name))
+    } else {
+        Ok(name)
+    }
+}
+
+/// Parses a top-level component (e.g. an element, a type or a group) of a schema.
+fn parse_component<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: Name,
+    ctx: &mut Context<'input>,
+) -> Result<(), ParseError>
+where
+    'a: 'input,
+{
+    if node.name() == "attributeGroup" {
+        crate::xsd::parse::attribute_group::parse(node, &name, ctx)?;
+        return Ok(());
+    }
+
+    let root = crate::xsd::parse::root::parse(node, &name, ctx)?;
+    ctx.add_root(name, root);
+
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum SchemaError {
     #[error("Error on line {row} (offset {col} in {file}): {err}")]
@@ -342,8 +805,24 @@ pub enum SchemaError {
         err: io::Error,
         file: String,
     },
+    #[error("Could not resolve schema location `{location}`")]
+    Unresolved { location: String },
+    #[error("Could not resolve the import of namespace `{namespace}` without a schema location")]
+    UnresolvedNamespace { namespace: String },
+    #[error("Invalid XML catalog {file}: {message}")]
+    Catalog { file: String, message: String },
+    #[error("No schema file given")]
+    NoSchema,
+    #[error("Circular include of schema files: {chain}")]
+    CircularInclude { chain: String },
     #[error("Element `{name}` not found in schema")]
     NotFound { name: String },
+    #[error("The base `{base}` of the simple content restriction `{name}` has no simple content")]
+    InvalidBase { name: String, base: String },
+    #[error("The type `{name}` is derived from itself")]
+    CircularType { name: String },
+    #[error("The facet `{facet}` of `{name}` does not apply to lists")]
+    UnsupportedFacet { name: String, facet: &'static str },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -368,3 +847,24 @@ impl ParseError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circular_type() {
+        let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+            <xs:simpleType name="a">
+                <xs:restriction base="b" />
+            </xs:simpleType>
+            <xs:simpleType name="b">
+                <xs:restriction base="a" />
+            </xs:simpleType>
+        </xs:schema>"#;
+        assert!(matches!(
+            Schema::parse(xsd, "circular.xsd"),
+            Err(SchemaError::CircularType { .. })
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+//! Raw XML captured by `<xs:any>` and `<xs:anyAttribute>` wildcards.
+
+use std::borrow::Cow;
+
+use xml::attribute::Attribute;
+use xml::name::Name;
+use xml::namespace::{Namespace, NS_NO_PREFIX};
+use xml::writer::events::XmlEvent;
+
+use crate::decode::Node;
+
+/// An element matched by an `<xs:any>` wildcard. The element is kept as is (including its
+/// attributes and children), so that it can be written back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnyElement {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub prefix: Option<String>,
+    pub attributes: Vec<AnyAttribute>,
+    pub children: Vec<AnyNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnyAttribute {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub prefix: Option<String>,
+    pub value: String,
+}
+
+/// The name of an attribute matched by an `<xs:anyAttribute>` wildcard. Names are compared by
+/// namespace and local name only, since the prefix is merely how a document refers to the namespace.
+#[derive(Debug, Clone)]
+pub struct QualifiedName {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyNode {
+    Element(AnyElement),
+    Text(String),
+}
+
+impl AnyElement {
+    pub fn from_xml_node(node: &Node<'_>) -> Self {
+        Self::from_roxmltree(node.node)
+    }
+
+    fn from_roxmltree(node: roxmltree::Node<'_, '_>) -> Self {
+        let tag_name = node.tag_name();
+        AnyElement {
+            name: tag_name.name().to_string(),
+            namespace: tag_name.namespace().map(String::from),
+            prefix: prefix(node, tag_name.namespace()),
+            attributes: node
+                .attributes()
+                .map(|attr| AnyAttribute {
+                    name: attr.name().to_string(),
+                    namespace: attr.namespace().map(String::from),
+                    prefix: prefix(node, attr.namespace()),
+                    value: attr.value().to_string(),
+                })
+                .collect(),
+            children: node
+                .children()
+                .filter_map(|child| {
+                    if child.is_element() {
+                        Some(AnyNode::Element(Self::from_roxmltree(child)))
+                    } else {
+                        child.text().map(|text| AnyNode::Text(text.to_string()))
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_xml_writer<W: std::io::Write>(
+        &self,
+        writer: &mut ::xml::writer::EventWriter<W>,
+    ) -> Result<(), xml::writer::Error> {
+        // declare all namespaces used by the element and its attributes (the writer omits the
+        // declarations that are already in scope)
+        let mut namespace = Namespace::empty();
+        if let Some(uri) = &self.namespace {
+            namespace.put(self.prefix.as_deref().unwrap_or(NS_NO_PREFIX), uri.as_str());
+        }
+        for attr in &self.attributes {
+            if let (Some(prefix), Some(uri)) = (&attr.prefix, &attr.namespace) {
+                namespace.put(prefix.as_str(), uri.as_str());
+            }
+        }
+
+        writer.write(XmlEvent::StartElement {
+            name: Name {
+                local_name: &self.name,
+                namespace: self.namespace.as_deref(),
+                prefix: self.prefix.as_deref(),
+            },
+            attributes: Cow::Owned(
+                self.attributes
+                    .iter()
+                    .map(|attr| {
+                        Attribute::new(
+                            Name {
+                                local_name: &attr.name,
+                                namespace: attr.namespace.as_deref(),
+                                prefix: attr.prefix.as_deref(),
+                            },
+                            &attr.value,
+                        )
+                    })
+                    .collect(),
+            ),
+            namespace: Cow::Owned(namespace),
+        })?;
+        for child in &self.children {
+            match child {
+                AnyNode::Element(el) => el.to_xml_writer(writer)?,
+                AnyNode::Text(text) => writer.write(XmlEvent::characters(text))?,
+            }
+        }
+        writer.write(XmlEvent::end_element())?;
+
+        Ok(())
+    }
+}
+
+impl QualifiedName {
+    /// An unqualified name (without namespace).
+    pub fn new(name: impl Into<String>) -> Self {
+        QualifiedName {
+            name: name.into(),
+            namespace: None,
+            prefix: None,
+        }
+    }
+
+    /// A name in the given namespace, which is declared using the given prefix when written.
+    pub fn with_namespace(
+        name: impl Into<String>,
+        namespace: impl Into<String>,
+        prefix: impl Into<String>,
+    ) -> Self {
+        QualifiedName {
+            name: name.into(),
+            namespace: Some(namespace.into()),
+            prefix: Some(prefix.into()),
+        }
+    }
+}
+
+impl QualifiedName {
+    fn key(&self) -> (Option<&str>, &str) {
+        (self.namespace.as_deref(), &self.name)
+    }
+}
+
+impl PartialEq for QualifiedName {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for QualifiedName {}
+
+impl PartialOrd for QualifiedName {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QualifiedName {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl std::hash::Hash for QualifiedName {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl<'a> From<&'a QualifiedName> for Name<'a> {
+    fn from(name: &'a QualifiedName) -> Self {
+        Name {
+            local_name: &name.name,
+            namespace: name.namespace.as_deref(),
+            prefix: name.prefix.as_deref(),
+        }
+    }
+}
+
+pub(crate) fn prefix(node: roxmltree::Node<'_, '_>, namespace: Option<&str>) -> Option<String> {
+    namespace
+        .and_then(|uri| node.lookup_prefix(uri))
+        .map(String::from)
+}
@@ -1,9 +1,11 @@
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::ops::Range;
 
 use thiserror::Error;
 
+use crate::any::{self, QualifiedName};
+
 #[derive(Debug, Error)]
 pub enum FromXmlError {
     #[error("XML error: {0}")]
@@ -13,6 +15,11 @@ pub enum FromXmlError {
         name: String,
         namespace: Option<String>,
     },
+    #[error("Unexpected element {name} ({namespace:?})")]
+    UnexpectedElement {
+        name: String,
+        namespace: Option<String>,
+    },
     #[error("Missing required attribute {name}")]
     MissingAttribute { name: String },
     #[error("Expected element {name} to contain text content")]
@@ -21,6 +28,8 @@ pub enum FromXmlError {
     InvalidVariant { name: String },
     #[error("Could not find valid variant for choice `{name}`")]
     MissingVariant { name: String },
+    #[error("Encountered unknown type {name} in xsi:type")]
+    UnknownType { name: String },
     #[error("Failed to parse type {type_} from {value}: {err}")]
     ParseType {
         type_: String,
@@ -32,13 +41,21 @@ pub enum FromXmlError {
         expected: &'static str,
         received: String,
     },
+    #[error("Value `{value}` violates {facet} restriction `{restriction}`")]
+    Restriction {
+        facet: &'static str,
+        restriction: &'static str,
+        value: String,
+    },
     #[error("infallible")]
     Infallible(#[from] std::convert::Infallible),
 }
 
+pub(crate) const NS_XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
 pub struct Document<'a>(roxmltree::Document<'a>);
 pub struct Node<'a> {
-    node: roxmltree::Node<'a, 'a>,
+    pub(crate) node: roxmltree::Node<'a, 'a>,
     children: RefCell<Children<'a>>,
 }
 
@@ -77,7 +94,7 @@ impl<'a> Node<'a> {
     fn new(node: roxmltree::Node<'a, 'a>) -> Self {
         Node {
             children: RefCell::new(Children {
-                children: Box::new(node.children().filter(|c| c.is_element())),
+                children: Box::new(node.children().filter(|c| c.is_element() || c.is_text())),
                 peeked: VecDeque::new(),
                 next: None,
             }),
@@ -86,30 +103,64 @@ impl<'a> Node<'a> {
     }
 
     pub fn peek_child(&self, name: &str, namespace: Option<&str>) -> bool {
+        self.peek_matching(|tag_name| tag_name.name() == name && tag_name.namespace() == namespace)
+    }
+
+    pub fn next_child(&self, name: &str, namespace: Option<&str>) -> Option<Node<'_>> {
+        self.next_matching(|tag_name| tag_name.name() == name && tag_name.namespace() == namespace)
+    }
+
+    /// Whether the next child is an element whose namespace is accepted by the given wildcard
+    /// `namespace` constraint (used for `<xs:any>`).
+    pub fn peek_any_child(&self, namespace: impl Fn(Option<&str>) -> bool) -> bool {
+        self.peek_matching(|tag_name| namespace(tag_name.namespace()))
+    }
+
+    /// Returns the next child if it is an element whose namespace is accepted by the given
+    /// wildcard `namespace` constraint (used for `<xs:any>`).
+    pub fn next_any_child(&self, namespace: impl Fn(Option<&str>) -> bool) -> Option<Node<'_>> {
+        self.next_matching(|tag_name| namespace(tag_name.namespace()))
+    }
+
+    pub fn try_next_any_child(
+        &self,
+        namespace: impl Fn(Option<&str>) -> bool,
+    ) -> Result<Node<'_>, FromXmlError> {
+        self.next_any_child(namespace)
+            .ok_or_else(|| FromXmlError::MissingElement {
+                name: "*".to_string(),
+                namespace: None,
+            })
+    }
+
+    fn peek_matching(&self, matches: impl Fn(&roxmltree::ExpandedName<'_, '_>) -> bool) -> bool {
         let mut children = self.children.borrow_mut();
+        children.skip_text();
         if children.next.is_none() {
             children.next = children
                 .peeked
                 .pop_front()
-                .or_else(|| children.children.next());
+                .or_else(|| children.next_element());
         }
         if let Some(next) = &children.next {
-            let tag_name = next.tag_name();
-            return tag_name.name() == name && tag_name.namespace() == namespace;
+            return matches(&next.tag_name());
         }
         false
     }
 
-    pub fn next_child(&self, name: &str, namespace: Option<&str>) -> Option<Node<'_>> {
+    fn next_matching(
+        &self,
+        matches: impl Fn(&roxmltree::ExpandedName<'_, '_>) -> bool,
+    ) -> Option<Node<'_>> {
         let mut children = self.children.borrow_mut();
+        children.skip_text();
         if let Some(next) = children
             .next
             .take()
             .or_else(|| children.peeked.pop_front())
-            .or_else(|| children.children.next())
+            .or_else(|| children.next_element())
         {
-            let tag_name = next.tag_name();
-            if tag_name.name() == name && tag_name.namespace() == namespace {
+            if matches(&next.tag_name()) {
                 return Some(Node::new(next));
             }
             children.next = Some(next)
@@ -131,6 +182,7 @@ impl<'a> Node<'a> {
 
     pub fn child(&self, name: &str, namespace: Option<&str>) -> Option<Node<'_>> {
         let mut children = self.children.borrow_mut();
+        children.skip_text();
         if let Some(next) = children.next.take() {
             let tag_name = next.tag_name();
             if tag_name.name() == name && tag_name.namespace() == namespace {
@@ -146,8 +198,7 @@ impl<'a> Node<'a> {
             }
         }
 
-        #[allow(clippy::while_let_on_iterator)]
-        while let Some(child) = children.children.next() {
+        while let Some(child) = children.next_element() {
             let tag_name = child.tag_name();
             if tag_name.name() == name && tag_name.namespace() == namespace {
                 return Some(Node::new(child));
@@ -166,6 +217,24 @@ impl<'a> Node<'a> {
             })
     }
 
+    /// Whether the next child is a text node (only of interest for mixed content).
+    pub fn peek_text(&self) -> bool {
+        let mut children = self.children.borrow_mut();
+        if children.next.is_none() && children.peeked.is_empty() {
+            children.next = children.children.next();
+        }
+        children.next.is_some_and(|next| next.is_text())
+    }
+
+    /// Returns the next child if it is a text node (only of interest for mixed content).
+    pub fn next_text(&self) -> Option<&'a str> {
+        if !self.peek_text() {
+            return None;
+        }
+        let mut children = self.children.borrow_mut();
+        children.next.take().and_then(|next| next.text())
+    }
+
     pub fn attribute(&self, name: &str) -> Option<&'a str> {
         self.node.attribute(name)
     }
@@ -177,6 +246,60 @@ impl<'a> Node<'a> {
             })
     }
 
+    /// The attribute `name` of the given namespace (used for qualified attributes).
+    pub fn attribute_ns(&self, name: &str, namespace: &str) -> Option<&'a str> {
+        self.node.attribute((namespace, name))
+    }
+
+    pub fn try_attribute_ns(&self, name: &str, namespace: &str) -> Result<&'a str, FromXmlError> {
+        self.attribute_ns(name, namespace)
+            .ok_or_else(|| FromXmlError::MissingAttribute {
+                name: name.to_string(),
+            })
+    }
+
+    /// Returns all attributes that are not `declared` (by their name and namespace) and whose
+    /// namespace is accepted by the given wildcard `namespace` constraint (used for
+    /// `<xs:anyAttribute>`). Attributes of the `xsi` namespace are never included, as they are
+    /// meant for the XML processor.
+    pub fn other_attributes(
+        &self,
+        declared: &[(&str, Option<&str>)],
+        namespace: impl Fn(Option<&str>) -> bool,
+    ) -> BTreeMap<QualifiedName, String> {
+        self.node
+            .attributes()
+            .filter(|attr| !declared.contains(&(attr.name(), attr.namespace())))
+            .filter(|attr| attr.namespace() != Some(NS_XSI) && namespace(attr.namespace()))
+            .map(|attr| {
+                (
+                    QualifiedName {
+                        name: attr.name().to_string(),
+                        namespace: attr.namespace().map(String::from),
+                        prefix: any::prefix(self.node, attr.namespace()),
+                    },
+                    attr.value().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the element is explicitly empty (`xsi:nil="true"`).
+    pub fn is_nil(&self) -> bool {
+        matches!(self.node.attribute((NS_XSI, "nil")), Some("true" | "1"))
+    }
+
+    /// The type given by the `xsi:type` attribute as its local name and namespace (resolved using
+    /// the namespaces in scope of the element).
+    pub fn xsi_type(&self) -> Option<(&'a str, Option<&'a str>)> {
+        let value = self.node.attribute((NS_XSI, "type"))?;
+        let (prefix, name) = match value.split_once(':') {
+            Some((prefix, name)) => (Some(prefix), name),
+            None => (None, value),
+        };
+        Some((name, self.node.lookup_namespace_uri(prefix)))
+    }
+
     pub fn text(&self) -> Result<&str, FromXmlError> {
         if self.node.first_child().is_none() {
             Ok("")
@@ -191,3 +314,16 @@ impl<'a> Node<'a> {
         self.node.range()
     }
 }
+
+impl<'a> Children<'a> {
+    /// Drops a pending text node, as text is ignored unless explicitly asked for.
+    fn skip_text(&mut self) {
+        if self.next.is_some_and(|next| next.is_text()) {
+            self.next = None;
+        }
+    }
+
+    fn next_element(&mut self) -> Option<roxmltree::Node<'a, 'a>> {
+        self.children.find(|child| child.is_element())
+    }
+}
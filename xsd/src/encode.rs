@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ToXmlError {
+    #[error("XML error: {0}")]
+    Xml(#[from] xml::writer::Error),
+    #[error("Value `{value}` violates {facet} restriction `{restriction}`")]
+    Restriction {
+        facet: &'static str,
+        restriction: &'static str,
+        value: String,
+    },
+}
@@ -0,0 +1,272 @@
+//! Validation of identity constraints (`<xs:key>`, `<xs:keyref>` and `<xs:unique>`).
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::encode::ToXmlError;
+
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    #[error("XML error: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("Failed to encode document: {0}")]
+    Encode(#[from] ToXmlError),
+    #[error("Duplicate value {values:?} for identity constraint `{constraint}`")]
+    Duplicate {
+        constraint: &'static str,
+        values: Vec<String>,
+    },
+    #[error("Missing field `{field}` of key `{constraint}`")]
+    MissingField {
+        constraint: &'static str,
+        field: &'static str,
+    },
+    #[error("Field `{field}` of identity constraint `{constraint}` matches more than one value")]
+    AmbiguousField {
+        constraint: &'static str,
+        field: &'static str,
+    },
+    #[error("Value {values:?} of keyref `{constraint}` does not match any `{refer}`")]
+    DanglingKeyRef {
+        constraint: &'static str,
+        refer: &'static str,
+        values: Vec<String>,
+    },
+    #[error("Identity constraint `{name}` not found")]
+    UnknownConstraint { name: &'static str },
+}
+
+#[derive(Debug)]
+pub struct IdentityConstraint {
+    /// The (name, namespace) of the constraint, which is what keyrefs refer to.
+    pub name: (&'static str, Option<&'static str>),
+    pub kind: IdentityKind,
+    /// The (local name, namespace) of the element the constraint is declared on.
+    pub element: (&'static str, Option<&'static str>),
+    pub selector: XPath,
+    pub fields: &'static [XPath],
+}
+
+#[derive(Debug)]
+pub enum IdentityKind {
+    Key,
+    Unique,
+    /// The (name, namespace) of the referenced key or unique constraint.
+    KeyRef {
+        refer: (&'static str, Option<&'static str>),
+    },
+}
+
+/// The restricted XPath subset allowed in selectors and fields, e.g. `.//item | list/entry`.
+#[derive(Debug)]
+pub struct XPath {
+    pub xpath: &'static str,
+    pub paths: &'static [Path],
+}
+
+#[derive(Debug)]
+pub struct Path {
+    /// Whether the path starts with `.//`.
+    pub is_descendant: bool,
+    pub steps: &'static [NameTest],
+    /// The final `@attribute` step (only allowed in fields).
+    pub attribute: Option<NameTest>,
+}
+
+#[derive(Debug)]
+pub enum NameTest {
+    /// `*`
+    Any,
+    /// `prefix:*`
+    Namespace(Option<&'static str>),
+    Name(&'static str, Option<&'static str>),
+}
+
+/// Validates the given `constraints` against all elements of the XML document they are declared
+/// on. Keyrefs are resolved against the keys of the same element or its descendants.
+pub fn validate(input: &str, constraints: &[IdentityConstraint]) -> Result<(), IdentityError> {
+    let doc = roxmltree::Document::parse(input)?;
+
+    // check keys first, so that keyrefs are only resolved against valid keys
+    let is_keyref = |c: &&IdentityConstraint| matches!(c.kind, IdentityKind::KeyRef { .. });
+    for constraint in constraints
+        .iter()
+        .filter(|c| !is_keyref(c))
+        .chain(constraints.iter().filter(is_keyref))
+    {
+        for scope in doc
+            .root_element()
+            .descendants()
+            .filter(|node| constraint.is_declared_on(node))
+        {
+            let mut seen = HashSet::new();
+            let refer = match constraint.kind {
+                IdentityKind::KeyRef { refer } => {
+                    let key = constraints
+                        .iter()
+                        .find(|c| c.name == refer)
+                        .ok_or(IdentityError::UnknownConstraint { name: refer.0 })?;
+                    Some((refer.0, key.table(scope)?))
+                }
+                _ => None,
+            };
+
+            for values in constraint.evaluate(scope)? {
+                let values = match (values, &constraint.kind) {
+                    (Ok(values), _) => values,
+                    (Err(field), IdentityKind::Key) => {
+                        return Err(IdentityError::MissingField {
+                            constraint: constraint.name.0,
+                            field,
+                        })
+                    }
+                    // tuples with missing fields are ignored by unique and keyref constraints
+                    (Err(_), _) => continue,
+                };
+
+                if let Some((refer, keys)) = &refer {
+                    if !keys.contains(&values) {
+                        return Err(IdentityError::DanglingKeyRef {
+                            constraint: constraint.name.0,
+                            refer,
+                            values,
+                        });
+                    }
+                } else if !seen.insert(values.clone()) {
+                    return Err(IdentityError::Duplicate {
+                        constraint: constraint.name.0,
+                        values,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl IdentityConstraint {
+    fn is_declared_on(&self, node: &roxmltree::Node<'_, '_>) -> bool {
+        let tag_name = node.tag_name();
+        node.is_element()
+            && tag_name.name() == self.element.0
+            && tag_name.namespace() == self.element.1
+    }
+
+    /// The field values of all nodes selected in `scope`, or the first field that is missing.
+    #[allow(clippy::type_complexity)]
+    fn evaluate(
+        &self,
+        scope: roxmltree::Node<'_, '_>,
+    ) -> Result<Vec<Result<Vec<String>, &'static str>>, IdentityError> {
+        self.selector
+            .select(scope)
+            .into_iter()
+            .map(|node| {
+                let mut values = Vec::with_capacity(self.fields.len());
+                for field in self.fields {
+                    let mut matches = field.values(node);
+                    match (matches.pop(), matches.is_empty()) {
+                        (Some(value), true) => values.push(value),
+                        (Some(_), false) => {
+                            return Err(IdentityError::AmbiguousField {
+                                constraint: self.name.0,
+                                field: field.xpath,
+                            })
+                        }
+                        (None, _) => return Ok(Err(field.xpath)),
+                    }
+                }
+                Ok(Ok(values))
+            })
+            .collect()
+    }
+
+    /// All complete values of this key or unique constraint within `scope` (including the
+    /// elements in `scope` declaring the constraint).
+    fn table(&self, scope: roxmltree::Node<'_, '_>) -> Result<HashSet<Vec<String>>, IdentityError> {
+        let mut table = HashSet::new();
+        for node in scope.descendants().filter(|node| self.is_declared_on(node)) {
+            table.extend(self.evaluate(node)?.into_iter().filter_map(Result::ok));
+        }
+        Ok(table)
+    }
+}
+
+impl XPath {
+    fn select<'a, 'input>(
+        &self,
+        node: roxmltree::Node<'a, 'input>,
+    ) -> Vec<roxmltree::Node<'a, 'input>> {
+        let mut result = self
+            .paths
+            .iter()
+            .flat_map(|path| path.select(node))
+            .collect::<Vec<_>>();
+
+        // alternatives of a union might select the same nodes
+        result.sort_by_key(|n| n.id().get());
+        result.dedup_by_key(|n| n.id().get());
+        result
+    }
+
+    fn values(&self, node: roxmltree::Node<'_, '_>) -> Vec<String> {
+        let mut values = Vec::new();
+        for path in self.paths {
+            let nodes = path.select(node);
+            match &path.attribute {
+                Some(test) => values.extend(
+                    nodes
+                        .into_iter()
+                        .flat_map(|n| n.attributes())
+                        .filter(|attr| test.matches(attr.name(), attr.namespace()))
+                        .map(|attr| attr.value().trim().to_string()),
+                ),
+                None => values.extend(nodes.into_iter().map(|n| {
+                    n.children()
+                        .filter(|c| c.is_text())
+                        .filter_map(|c| c.text())
+                        .collect::<String>()
+                        .trim()
+                        .to_string()
+                })),
+            }
+        }
+        values
+    }
+}
+
+impl Path {
+    fn select<'a, 'input>(
+        &self,
+        node: roxmltree::Node<'a, 'input>,
+    ) -> Vec<roxmltree::Node<'a, 'input>> {
+        let mut nodes = if self.is_descendant {
+            node.descendants().filter(|n| n.is_element()).collect()
+        } else {
+            vec![node]
+        };
+        for step in self.steps {
+            nodes = nodes
+                .into_iter()
+                .flat_map(|n| n.children())
+                .filter(|n| {
+                    let tag_name = n.tag_name();
+                    n.is_element() && step.matches(tag_name.name(), tag_name.namespace())
+                })
+                .collect();
+        }
+        nodes
+    }
+}
+
+impl NameTest {
+    fn matches(&self, name: &str, namespace: Option<&str>) -> bool {
+        match self {
+            NameTest::Any => true,
+            NameTest::Namespace(ns) => *ns == namespace,
+            NameTest::Name(n, ns) => *n == name && *ns == namespace,
+        }
+    }
+}
@@ -1,5 +1,14 @@
+pub mod any;
 pub mod decode;
+pub mod encode;
+pub mod identity;
+pub mod mixed;
+pub mod nillable;
+pub mod restriction;
 
+pub use any::{AnyAttribute, AnyElement, AnyNode, QualifiedName};
+pub use nillable::Nillable;
+pub use regex;
 pub use xml;
 pub use xsd_derive::all;
 pub use xsd_internal as internal;
@@ -21,6 +30,8 @@ pub struct ContextInner<'a> {
     name: Name<'a>,
     attributes: Vec<(Name<'a>, Cow<'a, str>)>,
     namespace: Namespace,
+    /// The default namespace of the document, whose elements are written without prefix.
+    default_ns: Option<&'static str>,
 }
 
 impl<'a, 'b> Context<'a, 'b> {
@@ -29,9 +40,34 @@ impl<'a, 'b> Context<'a, 'b> {
             name: name.into(),
             attributes: Vec::new(),
             namespace: Namespace::empty(),
+            default_ns: None,
         })
     }
 
+    /// The context of the child element `name`, which is prefixed if it is in a `namespace`. The
+    /// prefix is dropped for elements of the default namespace, and declared otherwise (unless it
+    /// is already in scope).
+    pub fn child<'c>(&self, name: &'a str, namespace: Option<&'static str>) -> Context<'a, 'c> {
+        let default_ns = match self {
+            Context::Virtual(inner) => inner.default_ns,
+            Context::Context(inner) => inner.default_ns,
+        };
+        let mut inner = ContextInner {
+            name: Name::from(name),
+            attributes: Vec::new(),
+            namespace: Namespace::empty(),
+            default_ns,
+        };
+        if let (Some(prefix), Some(uri)) = (inner.name.prefix, namespace) {
+            if default_ns == Some(uri) {
+                inner.name.prefix = None;
+            } else {
+                inner.namespace.put(prefix, uri);
+            }
+        }
+        Context::Context(inner)
+    }
+
     pub fn wrap<'c>(ctx: &'c mut Context<'a, 'b>) -> Context<'a, 'c> {
         match ctx {
             Context::Virtual(inner) => Context::Virtual(inner),
@@ -57,15 +93,28 @@ impl<'a, 'b> Context<'a, 'b> {
         }
     }
 
-    pub fn set_default_ns(&mut self, uri: impl Into<String>) {
-        match self {
-            Context::Virtual(inner) => {
-                inner.namespace.put(NS_NO_PREFIX, uri);
-            }
-            Context::Context(inner) => {
-                inner.namespace.put(NS_NO_PREFIX, uri);
-            }
-        }
+    /// Adds an `xsi:type` attribute, which is necessary when writing a derived type in place of
+    /// its base type.
+    pub fn set_xsi_type(&mut self, type_: impl Into<Cow<'a, str>>) {
+        self.set_ns("xsi", decode::NS_XSI);
+        self.set_attr("xsi:type", type_);
+    }
+
+    /// Adds an `xsi:nil="true"` attribute to mark the element as explicitly empty.
+    pub fn set_nil(&mut self) {
+        self.set_ns("xsi", decode::NS_XSI);
+        self.set_attr("xsi:nil", "true");
+    }
+
+    /// Declares the default namespace, which is inherited by the child elements (see
+    /// [Context::child]).
+    pub fn set_default_ns(&mut self, uri: &'static str) {
+        let inner = match self {
+            Context::Virtual(inner) => &mut **inner,
+            Context::Context(inner) => inner,
+        };
+        inner.namespace.put(NS_NO_PREFIX, uri);
+        inner.default_ns = Some(uri);
     }
 
     pub fn write_start_element<W: std::io::Write>(
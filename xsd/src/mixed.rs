@@ -0,0 +1,184 @@
+//! Helpers used by the generated code to check the child elements of mixed content, which is
+//! decoded into a flat list of elements and text regardless of its model group.
+
+use crate::decode::FromXmlError;
+
+/// The model group of mixed content and how often it may occur.
+#[derive(Debug, Clone, Copy)]
+pub enum Particle {
+    Sequence { min: u32, max: Option<u32> },
+    All { min: u32 },
+    Choice { min: u32, max: Option<u32> },
+}
+
+/// A child element declared by the model group (`max` is `None` for `unbounded`).
+#[derive(Debug, Clone, Copy)]
+pub struct Occurs {
+    pub name: &'static str,
+    pub namespace: Option<&'static str>,
+    pub min: u32,
+    pub max: Option<u32>,
+}
+
+/// Checks the child elements, given by the index of their declaration in `elements` (in document
+/// order), against the occurrences of the model group `particle`.
+pub fn check_occurrences(
+    children: impl IntoIterator<Item = usize>,
+    particle: Particle,
+    elements: &[Occurs],
+) -> Result<(), FromXmlError> {
+    match particle {
+        Particle::Sequence { min, max } => check_sequence(children, min, max, elements),
+        Particle::All { min } => check_all(children, min, elements),
+        Particle::Choice { min, max } => check_choice(children, min, max, elements),
+    }
+}
+
+fn check_sequence(
+    children: impl IntoIterator<Item = usize>,
+    min: u32,
+    max: Option<u32>,
+    elements: &[Occurs],
+) -> Result<(), FromXmlError> {
+    // the current element and how often it occurred in the current repetition
+    let mut current: Option<(usize, u32)> = None;
+    let mut repetitions = 0;
+    for index in children {
+        current = match current {
+            Some((prev, count)) if index == prev && below(count, elements[prev].max) => {
+                Some((prev, count + 1))
+            }
+            Some((prev, count)) if index > prev => {
+                check_min(&elements[prev], count)?;
+                check_skipped(&elements[prev + 1..index])?;
+                Some((index, 1))
+            }
+            _ => {
+                // the element starts the next repetition of the sequence
+                repetitions += 1;
+                if !below(repetitions - 1, max) {
+                    return Err(unexpected(&elements[index]));
+                }
+                if let Some((prev, count)) = current {
+                    check_min(&elements[prev], count)?;
+                    check_skipped(&elements[prev + 1..])?;
+                }
+                check_skipped(&elements[..index])?;
+                Some((index, 1))
+            }
+        };
+    }
+
+    if let Some((prev, count)) = current {
+        check_min(&elements[prev], count)?;
+        check_skipped(&elements[prev + 1..])?;
+    }
+
+    // the missing repetitions can only be empty if all elements are optional
+    if repetitions < min {
+        check_skipped(elements)?;
+    }
+    Ok(())
+}
+
+fn check_all(
+    children: impl IntoIterator<Item = usize>,
+    min: u32,
+    elements: &[Occurs],
+) -> Result<(), FromXmlError> {
+    let mut counts = vec![0; elements.len()];
+    for index in children {
+        counts[index] += 1;
+        if !below(counts[index] - 1, elements[index].max) {
+            return Err(unexpected(&elements[index]));
+        }
+    }
+
+    if min == 0 && counts.iter().all(|count| *count == 0) {
+        return Ok(());
+    }
+    for (element, count) in elements.iter().zip(counts) {
+        check_min(element, count)?;
+    }
+    Ok(())
+}
+
+fn check_choice(
+    children: impl IntoIterator<Item = usize>,
+    min: u32,
+    max: Option<u32>,
+    elements: &[Occurs],
+) -> Result<(), FromXmlError> {
+    // the element chosen by the current repetition and how often it occurred
+    let mut current: Option<(usize, u32)> = None;
+    let mut repetitions = 0;
+    for index in children {
+        current = match current {
+            Some((prev, count)) if index == prev && below(count, elements[prev].max) => {
+                Some((prev, count + 1))
+            }
+            _ => {
+                repetitions += 1;
+                if !below(repetitions - 1, max) {
+                    return Err(unexpected(&elements[index]));
+                }
+                if let Some((prev, count)) = current {
+                    check_min(&elements[prev], count)?;
+                }
+                Some((index, 1))
+            }
+        };
+    }
+    if let Some((prev, count)) = current {
+        check_min(&elements[prev], count)?;
+    }
+
+    // the missing repetitions can only be empty if one of the elements is optional
+    if repetitions < min && elements.iter().all(|element| element.min > 0) {
+        return Err(match elements {
+            [element] => missing(element),
+            _ => FromXmlError::MissingVariant {
+                name: elements
+                    .iter()
+                    .map(|element| element.name)
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            },
+        });
+    }
+    Ok(())
+}
+
+/// Whether `count` is below the maximum number of occurrences.
+fn below(count: u32, max: Option<u32>) -> bool {
+    max.is_none_or(|max| count < max)
+}
+
+fn check_min(element: &Occurs, count: u32) -> Result<(), FromXmlError> {
+    if count < element.min {
+        return Err(missing(element));
+    }
+    Ok(())
+}
+
+/// Checks that the given elements, which do not occur, are optional.
+fn check_skipped(elements: &[Occurs]) -> Result<(), FromXmlError> {
+    match elements.iter().find(|element| element.min > 0) {
+        Some(element) => Err(missing(element)),
+        None => Ok(()),
+    }
+}
+
+fn missing(element: &Occurs) -> FromXmlError {
+    FromXmlError::MissingElement {
+        name: element.name.to_string(),
+        namespace: element.namespace.map(String::from),
+    }
+}
+
+fn unexpected(element: &Occurs) -> FromXmlError {
+    FromXmlError::UnexpectedElement {
+        name: element.name.to_string(),
+        namespace: element.namespace.map(String::from),
+    }
+}
@@ -0,0 +1,35 @@
+//! Values of elements declared with `nillable="true"`.
+
+/// The value of a nillable element, which is either an actual value or explicitly empty
+/// (`xsi:nil="true"`). An absent element is represented by an `Option` around it instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nillable<T> {
+    Nil,
+    Value(T),
+}
+
+impl<T> Nillable<T> {
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Nillable::Nil)
+    }
+
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Nillable::Nil => None,
+            Nillable::Value(val) => Some(val),
+        }
+    }
+
+    pub fn into_value(self) -> Option<T> {
+        match self {
+            Nillable::Nil => None,
+            Nillable::Value(val) => Some(val),
+        }
+    }
+}
+
+impl<T> From<T> for Nillable<T> {
+    fn from(val: T) -> Self {
+        Nillable::Value(val)
+    }
+}
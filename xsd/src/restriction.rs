@@ -0,0 +1,58 @@
+//! Helpers used by the generated code to check values against the facets of a restricted
+//! `<xs:simpleType>`.
+
+use crate::decode::FromXmlError;
+use crate::encode::ToXmlError;
+
+/// A value that violates a facet of a restricted type (e.g. when constructing it with `new`).
+#[derive(Debug, thiserror::Error)]
+#[error("Value `{value}` violates {facet} restriction `{restriction}`")]
+pub struct RestrictionError {
+    pub facet: &'static str,
+    pub restriction: &'static str,
+    pub value: String,
+}
+
+impl From<RestrictionError> for FromXmlError {
+    fn from(err: RestrictionError) -> Self {
+        FromXmlError::Restriction {
+            facet: err.facet,
+            restriction: err.restriction,
+            value: err.value,
+        }
+    }
+}
+
+impl From<RestrictionError> for ToXmlError {
+    fn from(err: RestrictionError) -> Self {
+        ToXmlError::Restriction {
+            facet: err.facet,
+            restriction: err.restriction,
+            value: err.value,
+        }
+    }
+}
+
+/// Returns the number of total and fraction digits of the decimal value given in its lexical
+/// representation (e.g. `-012.340` has 4 total and 2 fraction digits). Leading and trailing zeros
+/// are not significant and are therefore not counted.
+pub fn digits(value: &str) -> (usize, usize) {
+    let value = value.trim().trim_start_matches(['+', '-']);
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    (integer.len() + fraction.len(), fraction.len())
+}
+
+/// Returns the number of octets encoded by the given `xs:base64Binary` value.
+pub fn base64_length(value: &str) -> usize {
+    let mut len = 0usize;
+    let mut padding = 0;
+    for c in value.chars().filter(|c| !c.is_whitespace()) {
+        len += 1;
+        if c == '=' {
+            padding += 1;
+        }
+    }
+    (len / 4 * 3).saturating_sub(padding)
+}
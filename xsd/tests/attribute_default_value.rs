@@ -0,0 +1,42 @@
+#[xsd::all(
+    schema = "tests/xsd/attribute_default_value.xsd",
+    attribute_defaults = true,
+    omit_default_attributes = true
+)]
+mod schema {}
+
+#[xsd::all(
+    schema = "tests/xsd/attribute_default_value.xsd",
+    attribute_defaults = true
+)]
+mod schema_keep_defaults {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn attribute_default_value() {
+    let xml = include_str!("./xsd/attribute_default_value.xml");
+    let expected = schema::Text {
+        text: "foobar".to_string(),
+        lang: "en".to_string(),
+        priority: 2,
+    };
+    assert_eq!(schema::Text::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn attribute_default_value_keep_defaults() {
+    let xml = include_str!("./xsd/attribute_default_value.xml");
+    let expected = schema_keep_defaults::Text {
+        text: "foobar".to_string(),
+        lang: "en".to_string(),
+        priority: 2,
+    };
+    assert_eq!(schema_keep_defaults::Text::from_xml(xml).unwrap(), expected);
+    assert_eq!(
+        String::from_utf8_lossy(&expected.to_xml().unwrap()),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<text lang="en" priority="2">foobar</text>"#
+    );
+}
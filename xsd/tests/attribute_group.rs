@@ -0,0 +1,19 @@
+#[xsd::all(schema = "tests/xsd/attribute_group.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn attribute_group() {
+    let xml = include_str!("./xsd/attribute_group.xml");
+    let expected = schema::Note {
+        note: "Hello".to_string(),
+        author: Some("Jane".to_string()),
+        id: "n1".to_string(),
+        lang: Some("en".to_string()),
+        editor: "Joe".to_string(),
+        revision: Some(3),
+    };
+    assert_eq!(schema::Note::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
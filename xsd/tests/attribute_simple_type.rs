@@ -8,7 +8,7 @@ fn attribute_simple_type() {
     let xml = include_str!("./xsd/attribute_simple_type.xml");
     let expected = schema::Text {
         text: "Deutsch".to_string(),
-        lang: "DE".to_string(),
+        lang: schema::TextLangData::new("DE".to_string()).unwrap(),
     };
     assert_eq!(schema::Text::from_xml(xml).unwrap(), expected);
     assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
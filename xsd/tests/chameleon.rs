@@ -0,0 +1,19 @@
+#[xsd::all(schema = "tests/xsd/chameleon.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn chameleon() {
+    let xml = include_str!("./xsd/chameleon.xml");
+    let expected = schema::Article {
+        content: schema::ArticleContent {
+            excerpt: "Lorem Ipsum".to_string(),
+            author: schema::Author {
+                name: "Foobar".to_string(),
+            },
+        },
+    };
+    assert_eq!(schema::Article::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,26 @@
+use xsd::internal::xsd::schema::{Schema, SchemaError};
+
+#[test]
+fn circular_include() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/xsd/circular_include.xsd"
+    );
+    match Schema::parse_file(path) {
+        Err(SchemaError::CircularInclude { chain }) => {
+            let files = chain
+                .split(" -> ")
+                .map(|file| file.rsplit('/').next().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                files,
+                vec![
+                    "circular_include.xsd",
+                    "circular_include_other.xsd",
+                    "circular_include.xsd"
+                ]
+            );
+        }
+        result => panic!("expected circular include error, got {:?}", result),
+    }
+}
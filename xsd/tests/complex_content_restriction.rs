@@ -0,0 +1,17 @@
+#[xsd::all(schema = "tests/xsd/complex_content_restriction.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn complex_content_restriction() {
+    let xml = include_str!("./xsd/complex_content_restriction.xml");
+    let expected = schema::ShortAddress {
+        name: "Foobar".to_string(),
+        street: Some("Main Street 1".to_string()),
+        id: 42,
+        country: Some(schema::Country::De),
+    };
+    assert_eq!(schema::ShortAddress::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
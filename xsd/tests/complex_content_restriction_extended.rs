@@ -0,0 +1,17 @@
+#[xsd::all(schema = "tests/xsd/complex_content_restriction_extended.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn complex_content_restriction_extended() {
+    // the restriction keeps the attributes the base inherits from its own base
+    let xml = include_str!("./xsd/complex_content_restriction_extended.xml");
+    let expected = schema::Root(schema::Restricted {
+        x: "Foobar".to_string(),
+        ga: Some("1".to_string()),
+        ba: Some("2".to_string()),
+    });
+    assert_eq!(schema::Root::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,41 @@
+#[xsd::all(schema = "tests/xsd/complex_type_any_attribute.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::QualifiedName;
+
+#[test]
+fn complex_type_any_attribute() {
+    let xml = include_str!("./xsd/complex_type_any_attribute.xml");
+    let expected = schema::Item {
+        name: "Shirt".to_string(),
+        id: Some("1".to_string()),
+        other_attributes: [
+            (
+                QualifiedName::with_namespace("color", "urn:ext", "ext"),
+                "red".to_string(),
+            ),
+            (
+                QualifiedName::with_namespace("size", "urn:ext", "ext"),
+                "L".to_string(),
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    };
+    assert_eq!(schema::Item::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn complex_type_any_attribute_prefix() {
+    let xml = include_str!("./xsd/complex_type_any_attribute.xml");
+    let other = include_str!("./xsd/complex_type_any_attribute_prefix.xml");
+    let item = schema::Item::from_xml(other).unwrap();
+    assert_eq!(item, schema::Item::from_xml(xml).unwrap());
+    assert_eq!(
+        item.other_attributes
+            .get(&QualifiedName::with_namespace("color", "urn:ext", "ext")),
+        Some(&"red".to_string())
+    );
+}
@@ -0,0 +1,21 @@
+#[xsd::all(schema = "tests/xsd/complex_type_extension_any_attribute.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::QualifiedName;
+
+#[test]
+fn complex_type_extension_any_attribute() {
+    let xml = include_str!("./xsd/complex_type_extension_any_attribute.xml");
+    let expected = schema::Root {
+        entity: schema::Entity {
+            id: Some("1".to_string()),
+        },
+        role: Some("r".to_string()),
+        other_attributes: [(QualifiedName::new("foo"), "bar".to_string())]
+            .into_iter()
+            .collect(),
+    };
+    assert_eq!(schema::Root::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,21 @@
+#[xsd::all(schema = "tests/xsd/complex_type_mixed.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn complex_type_mixed() {
+    let xml = include_str!("./xsd/complex_type_mixed.xml");
+    let expected = schema::Paragraph {
+        content: vec![
+            schema::ParagraphContent::Text("Hello ".to_string()),
+            schema::ParagraphContent::B("bold".to_string()),
+            schema::ParagraphContent::Text(" and ".to_string()),
+            schema::ParagraphContent::I("italic".to_string()),
+            schema::ParagraphContent::Text("!".to_string()),
+        ],
+        lang: Some("en".to_string()),
+    };
+    assert_eq!(schema::Paragraph::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,65 @@
+#[xsd::all(schema = "tests/xsd/complex_type_mixed_sequence.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::decode::FromXmlError;
+
+#[test]
+fn complex_type_mixed_sequence() {
+    let xml = include_str!("./xsd/complex_type_mixed_sequence.xml");
+    let expected = schema::Letter {
+        content: vec![
+            schema::LetterContent::Text("Dear ".to_string()),
+            schema::LetterContent::Name("Jane".to_string()),
+            schema::LetterContent::Text(", your ".to_string()),
+            schema::LetterContent::Item("book".to_string()),
+            schema::LetterContent::Text(" and ".to_string()),
+            schema::LetterContent::Item("pen".to_string()),
+            schema::LetterContent::Text(" ship on ".to_string()),
+            schema::LetterContent::Date("Monday".to_string()),
+            schema::LetterContent::Text(".".to_string()),
+        ],
+    };
+    assert_eq!(schema::Letter::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn complex_type_mixed_sequence_occurrences() {
+    // the elements of the sequence are checked despite being decoded as a list
+    let cases = [
+        ("<letter>Hi <date>Monday</date></letter>", "name"),
+        ("<letter><name>Jane</name></letter>", "date"),
+        (
+            "<letter><date>Monday</date><name>Jane</name></letter>",
+            "name",
+        ),
+    ];
+    for (xml, expected_name) in cases {
+        match schema::Letter::from_xml(xml) {
+            Err(FromXmlError::MissingElement { name, .. }) => assert_eq!(name, expected_name),
+            result => panic!("expected missing {expected_name}, got {result:?}"),
+        }
+    }
+
+    let xml = "<letter><name>Jane</name><item>a</item><item>b</item><item>c</item>\
+        <date>Monday</date></letter>";
+    match schema::Letter::from_xml(xml) {
+        Err(FromXmlError::UnexpectedElement { name, .. }) => assert_eq!(name, "item"),
+        result => panic!("expected unexpected item, got {result:?}"),
+    }
+}
+
+#[test]
+fn complex_type_mixed_sequence_repetitions() {
+    // the sequence must occur at least twice
+    let xml = "<memo>To <name>Jane</name> on <date>Monday</date></memo>";
+    match schema::Memo::from_xml(xml) {
+        Err(FromXmlError::MissingElement { name, .. }) => assert_eq!(name, "name"),
+        result => panic!("expected missing name, got {result:?}"),
+    }
+
+    let xml = "<memo><name>Jane</name><date>Monday</date><name>John</name><date>Friday</date>\
+        </memo>";
+    assert!(schema::Memo::from_xml(xml).is_ok());
+}
@@ -0,0 +1,71 @@
+#[xsd::all(schema = "tests/xsd/complex_type_xsi_type.xsd", xsi_type = true)]
+mod schema {}
+
+#[xsd::all(schema = "tests/xsd/complex_type_xsi_type.xsd")]
+mod plain {}
+
+use pretty_assertions::assert_eq;
+use xsd::decode::FromXmlError;
+
+#[test]
+fn complex_type_xsi_type() {
+    let xml = include_str!("./xsd/complex_type_xsi_type.xml");
+    let expected = schema::Garage {
+        kind: Some(schema::VehicleDerived::Used),
+        vehicle: vec![
+            schema::VehicleDerived2::Vehicle(schema::Vehicle {
+                name: "Bike".to_string(),
+            }),
+            schema::VehicleDerived2::Car(schema::Car {
+                base: schema::Vehicle {
+                    name: "Beetle".to_string(),
+                },
+                vehicle: schema::CarVehicle { doors: 2 },
+            }),
+            schema::VehicleDerived2::FireTruck(schema::FireTruck {
+                truck: schema::Truck {
+                    vehicle: schema::Vehicle {
+                        name: "Engine 1".to_string(),
+                    },
+                    load: 10,
+                },
+                ladder: true,
+            }),
+        ],
+    };
+    assert_eq!(schema::Garage::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn complex_type_xsi_type_unknown() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<garage xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <vehicle xsi:type="Boat">
+    <name>Titanic</name>
+  </vehicle>
+</garage>"#;
+    assert!(matches!(
+        schema::Garage::from_xml(xml),
+        Err(FromXmlError::UnknownType { name }) if name == "Boat"
+    ));
+}
+
+#[test]
+fn complex_type_xsi_type_disabled() {
+    // without `xsi_type`, extended types are used as they are
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<garage>
+  <vehicle>
+    <name>Bike</name>
+  </vehicle>
+</garage>"#;
+    let expected = plain::Garage {
+        kind: None,
+        vehicle: vec![plain::Vehicle {
+            name: "Bike".to_string(),
+        }],
+    };
+    assert_eq!(plain::Garage::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
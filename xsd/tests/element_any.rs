@@ -0,0 +1,40 @@
+#[xsd::all(schema = "tests/xsd/element_any.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::{AnyAttribute, AnyElement, AnyNode};
+
+#[test]
+fn element_any() {
+    let xml = include_str!("./xsd/element_any.xml");
+    let ext = |name: &str, attributes, children| AnyElement {
+        name: name.to_string(),
+        namespace: Some("urn:ext".to_string()),
+        prefix: Some("ext".to_string()),
+        attributes,
+        children,
+    };
+    let expected = schema::Order {
+        id: "42".to_string(),
+        any: Some(vec![ext(
+            "gift",
+            vec![AnyAttribute {
+                name: "wrapped".to_string(),
+                namespace: Some("urn:ext".to_string()),
+                prefix: Some("ext".to_string()),
+                value: "true".to_string(),
+            }],
+            vec![
+                AnyNode::Text("Happy ".to_string()),
+                AnyNode::Element(ext(
+                    "b",
+                    Vec::new(),
+                    vec![AnyNode::Text("birthday".to_string())],
+                )),
+                AnyNode::Text("!".to_string()),
+            ],
+        )]),
+    };
+    assert_eq!(schema::Order::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,27 @@
+#[xsd::all(schema = "tests/xsd/element_any_multiple.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::{AnyElement, AnyNode};
+
+#[test]
+fn element_any_multiple() {
+    let xml = include_str!("./xsd/element_any_multiple.xml");
+    let any = |prefix: &str, name: &str, text: &str| AnyElement {
+        name: name.to_string(),
+        namespace: Some(format!("urn:{prefix}")),
+        prefix: Some(prefix[..1].to_string()),
+        attributes: Vec::new(),
+        children: vec![AnyNode::Text(text.to_string())],
+    };
+    let expected = schema::Message {
+        any: any("header", "header", "top"),
+        id: "1".to_string(),
+        any_2: Some(any("footer", "footer", "bottom")),
+        note: schema::Note {
+            note_data: schema::NoteData::Any2(any("b", "text", "hello")),
+        },
+    };
+    assert_eq!(schema::Message::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,22 @@
+#[xsd::all(schema = "tests/xsd/element_any_name.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::{AnyElement, AnyNode};
+
+#[test]
+fn element_any_name() {
+    let xml = include_str!("./xsd/element_any_name.xml");
+    let expected = schema::Message {
+        any_2: AnyElement {
+            name: "header".to_string(),
+            namespace: Some("urn:header".to_string()),
+            prefix: Some("h".to_string()),
+            attributes: Vec::new(),
+            children: vec![AnyNode::Text("top".to_string())],
+        },
+        any: "1".to_string(),
+    };
+    assert_eq!(schema::Message::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,49 @@
+#[xsd::all(
+    schema = "tests/xsd/element_default_value.xsd",
+    element_defaults = true
+)]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::decode::FromXmlError;
+
+#[test]
+fn element_default_value() {
+    let xml = include_str!("./xsd/element_default_value.xml");
+    let expected = schema::Settings {
+        debug: false,
+        level: 3,
+        side: schema::Side::Right,
+    };
+    assert_eq!(schema::Settings::from_xml(xml).unwrap(), expected);
+    assert_eq!(
+        String::from_utf8_lossy(&expected.to_xml().unwrap()),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<settings>
+  <debug>false</debug>
+  <level>3</level>
+  <side>RIGHT</side>
+</settings>"#
+    );
+}
+
+#[test]
+fn element_default_value_present() {
+    let xml = r#"<settings><debug>true</debug><level>1</level><side>LEFT</side></settings>"#;
+    let expected = schema::Settings {
+        debug: true,
+        level: 1,
+        side: schema::Side::Left,
+    };
+    assert_eq!(schema::Settings::from_xml(xml).unwrap(), expected);
+}
+
+#[test]
+fn element_default_value_required_missing() {
+    // the default only fills an empty element, a required element must still be present
+    let xml = r#"<settings><debug>true</debug></settings>"#;
+    match schema::Settings::from_xml(xml) {
+        Err(FromXmlError::MissingElement { name, .. }) => assert_eq!(name, "level"),
+        result => panic!("expected missing element, got {result:?}"),
+    }
+}
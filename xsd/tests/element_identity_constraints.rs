@@ -0,0 +1,53 @@
+#[xsd::all(schema = "tests/xsd/element_identity_constraints.xsd")]
+mod schema {}
+
+use xsd::identity::IdentityError;
+
+#[test]
+fn element_identity_constraints() {
+    let xml = include_str!("./xsd/element_identity_constraints.xml");
+    let config = schema::Config::from_xml(xml).unwrap();
+    config.validate_identity().unwrap();
+    assert_eq!(String::from_utf8_lossy(&config.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn element_identity_constraints_violations() {
+    let xml = include_str!("./xsd/element_identity_constraints.xml");
+    let config = schema::Config::from_xml(xml).unwrap();
+
+    let mut duplicate_key = config.clone();
+    duplicate_key.servers.server[1].id = "a".to_string();
+    match duplicate_key.validate_identity() {
+        Err(IdentityError::Duplicate { constraint, values }) => {
+            assert_eq!(constraint, "serverId");
+            assert_eq!(values, vec!["a".to_string()]);
+        }
+        result => panic!("expected duplicate key, got {:?}", result),
+    }
+
+    let mut dangling_keyref = config.clone();
+    dangling_keyref.routes.route[1].server = "c".to_string();
+    match dangling_keyref.validate_identity() {
+        Err(IdentityError::DanglingKeyRef {
+            constraint,
+            refer,
+            values,
+        }) => {
+            assert_eq!(constraint, "routeServer");
+            assert_eq!(refer, "serverId");
+            assert_eq!(values, vec!["c".to_string()]);
+        }
+        result => panic!("expected dangling keyref, got {:?}", result),
+    }
+
+    let mut duplicate_unique = config;
+    duplicate_unique.routes.route[1].path = "/api".to_string();
+    match duplicate_unique.validate_identity() {
+        Err(IdentityError::Duplicate { constraint, values }) => {
+            assert_eq!(constraint, "routePath");
+            assert_eq!(values, vec!["/api".to_string()]);
+        }
+        result => panic!("expected duplicate unique value, got {:?}", result),
+    }
+}
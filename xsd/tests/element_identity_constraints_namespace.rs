@@ -0,0 +1,25 @@
+#[xsd::all(schema = "tests/xsd/element_identity_constraints_namespace.xsd")]
+mod schema {}
+
+use xsd::identity::IdentityError;
+
+#[test]
+fn element_identity_constraints_namespace() {
+    let xml = include_str!("./xsd/element_identity_constraints_namespace.xml");
+    let library = schema::main::Library::from_xml(xml).unwrap();
+    library.validate_identity().unwrap();
+    assert_eq!(String::from_utf8_lossy(&library.to_xml().unwrap()), xml);
+
+    // the keyref refers to the key of the other namespace, not to the one of the same name
+    let mut dangling_keyref = library.clone();
+    dangling_keyref.loan[0].code = "X".to_string();
+    match dangling_keyref.validate_identity() {
+        Err(IdentityError::DanglingKeyRef {
+            constraint, refer, ..
+        }) => {
+            assert_eq!(constraint, "loanCode");
+            assert_eq!(refer, "code");
+        }
+        result => panic!("expected dangling keyref, got {:?}", result),
+    }
+}
@@ -0,0 +1,40 @@
+#[xsd::all(schema = "tests/xsd/element_nillable.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::Nillable;
+
+#[test]
+fn element_nillable() {
+    let xml = include_str!("./xsd/element_nillable.xml");
+    let expected = schema::Person {
+        name: "Jane".to_string(),
+        email: Some(Nillable::Nil),
+        phone: vec![Nillable::Value("555-1234".to_string()), Nillable::Nil],
+        address: Some(Nillable::Nil),
+    };
+    assert_eq!(schema::Person::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn element_nillable_absent() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<person>
+  <name>Jane</name>
+  <phone>555-1234</phone>
+  <address>
+    <city>Berlin</city>
+  </address>
+</person>"#;
+    let expected = schema::Person {
+        name: "Jane".to_string(),
+        email: None,
+        phone: vec![Nillable::Value("555-1234".to_string())],
+        address: Some(Nillable::Value(schema::Address {
+            city: "Berlin".to_string(),
+        })),
+    };
+    assert_eq!(schema::Person::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
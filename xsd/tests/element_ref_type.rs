@@ -7,7 +7,7 @@ use pretty_assertions::assert_eq;
 fn element_ref_type() {
     let xml = include_str!("./xsd/element_ref_type.xml");
     let expected = schema::Address {
-        zip: "12345".to_string(),
+        zip: schema::Zip::new("12345".to_string()).unwrap(),
     };
     assert_eq!(schema::Address::from_xml(xml).unwrap(), expected);
     assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
@@ -0,0 +1,32 @@
+#[xsd::all(schema = "tests/xsd/element_substitution_group.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn element_substitution_group() {
+    let xml = include_str!("./xsd/element_substitution_group.xml");
+    let expected = schema::Drawing {
+        shape: vec![
+            schema::ShapeSubstitution::Circle(schema::CircleType {
+                shape_type: schema::ShapeType {
+                    color: Some("red".to_string()),
+                },
+                radius: 2,
+            }),
+            schema::ShapeSubstitution::Tile(schema::SquareType {
+                shape_type: schema::ShapeType { color: None },
+                size: 3,
+            }),
+            schema::ShapeSubstitution::Square(schema::SquareType {
+                shape_type: schema::ShapeType {
+                    color: Some("blue".to_string()),
+                },
+                size: 4,
+            }),
+        ],
+        title: "Shapes".to_string(),
+    };
+    assert_eq!(schema::Drawing::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
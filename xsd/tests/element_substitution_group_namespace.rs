@@ -0,0 +1,24 @@
+#[xsd::all(schema = "tests/xsd/element_substitution_group_namespace.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn element_substitution_group_namespace() {
+    let xml = include_str!("./xsd/element_substitution_group_namespace.xml");
+    let expected = schema::main::Drawing {
+        shape: vec![
+            schema::shapes::ShapeSubstitution2::Circle(schema::shapes::ShapeType {
+                color: Some("red".to_string()),
+            }),
+            schema::shapes::ShapeSubstitution2::Square(schema::shapes::ShapeType {
+                color: Some("blue".to_string()),
+            }),
+        ],
+        legend: schema::shapes::ShapeSubstitution {
+            text: "Shapes".to_string(),
+        },
+    };
+    assert_eq!(schema::main::Drawing::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
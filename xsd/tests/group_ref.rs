@@ -0,0 +1,34 @@
+#[xsd::all(schema = "tests/xsd/group_ref.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn group_ref() {
+    let xml = include_str!("./xsd/group_ref.xml");
+    let expected = schema::Customer {
+        name: "Jane".to_string(),
+        contact: schema::ContactGroup2 {
+            email: "jane@example.com".to_string(),
+            phone: None,
+        },
+        payment: vec![
+            schema::PaymentGroup::Iban("DE00123456780000000000".to_string()),
+            schema::PaymentGroup::Card("4111111111111111".to_string()),
+        ],
+        referrer: schema::Person {
+            base: schema::Contact {
+                kind: "friend".to_string(),
+            },
+            contact: schema::ContactGroup2 {
+                email: "john@example.com".to_string(),
+                phone: Some("0123".to_string()),
+            },
+        },
+        team: schema::ContactGroup {
+            lead: "Joe".to_string(),
+        },
+    };
+    assert_eq!(schema::Customer::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
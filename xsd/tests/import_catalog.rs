@@ -0,0 +1,68 @@
+#[xsd::all(
+    schema = "tests/xsd/import_catalog.xsd",
+    catalog = "tests/xsd/vendor/catalog.xml"
+)]
+mod schema {}
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+use xsd::internal::xsd::resolver::{ResolvedSchema, SchemaResolver};
+use xsd::internal::xsd::schema::{Schema, SchemaError};
+
+#[test]
+fn import_catalog() {
+    let xml = include_str!("./xsd/import_catalog.xml");
+    let expected = schema::Customer {
+        name: "Foobar".to_string(),
+        address: schema::Address {
+            street: "Main Street 1".to_string(),
+            city: "Berlin".to_string(),
+        },
+    };
+    assert_eq!(schema::Customer::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn import_unresolved() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/xsd/import_catalog.xsd");
+    match Schema::parse_file(path) {
+        Err(SchemaError::Unresolved { location }) => {
+            assert_eq!(location, "http://example.com/schemas/address.xsd");
+        }
+        result => panic!("expected unresolved schema error, got {:?}", result),
+    }
+}
+
+/// Serves the imported schema from memory.
+struct MemoryResolver;
+
+impl SchemaResolver for MemoryResolver {
+    fn resolve(
+        &self,
+        location: Option<&str>,
+        _namespace: Option<&str>,
+        _base: &Path,
+    ) -> io::Result<Option<ResolvedSchema>> {
+        Ok(
+            (location == Some("http://example.com/schemas/address.xsd")).then(|| ResolvedSchema {
+                path: PathBuf::from("memory/address.xsd"),
+                data: include_str!("./xsd/vendor/address.xsd").to_string(),
+            }),
+        )
+    }
+}
+
+#[test]
+fn import_custom_resolver() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/xsd/import_catalog.xsd");
+    let schema = Schema::parse_file_with_resolver(path, &MemoryResolver).unwrap();
+    let mut names = schema
+        .elements()
+        .map(|(name, _)| name.name.as_str())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["Address", "customer"]);
+}
@@ -0,0 +1,18 @@
+#[xsd::all(schema = "tests/xsd/import_namespace.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn import_namespace() {
+    let xml = include_str!("./xsd/import_namespace.xml");
+    let expected = schema::Customer {
+        name: "Foobar".to_string(),
+        address: schema::Address {
+            street: "Main Street 1".to_string(),
+            city: "Berlin".to_string(),
+        },
+    };
+    assert_eq!(schema::Customer::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
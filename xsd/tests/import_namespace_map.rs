@@ -0,0 +1,36 @@
+#[xsd::all(
+    schema = "tests/xsd/import_namespace_map.xsd",
+    import(namespace = "urn:address", schema = "tests/xsd/vendor/address.xsd")
+)]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::internal::xsd::schema::{Schema, SchemaError};
+
+#[test]
+fn import_namespace_map() {
+    let xml = include_str!("./xsd/import_namespace_map.xml");
+    let expected = schema::Customer {
+        name: "Foobar".to_string(),
+        address: schema::Address {
+            street: "Main Street 1".to_string(),
+            city: "Berlin".to_string(),
+        },
+    };
+    assert_eq!(schema::Customer::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn import_namespace_unresolved() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/xsd/import_namespace_map.xsd"
+    );
+    match Schema::parse_file(path) {
+        Err(SchemaError::UnresolvedNamespace { namespace }) => {
+            assert_eq!(namespace, "urn:address");
+        }
+        result => panic!("expected unresolved namespace error, got {:?}", result),
+    }
+}
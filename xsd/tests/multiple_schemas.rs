@@ -0,0 +1,51 @@
+#[xsd::all(schema = [
+    "tests/xsd/multiple_schemas.xsd",
+    "tests/xsd/multiple_schemas_*.xsd"
+])]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+fn address() -> schema::common::Address {
+    schema::common::Address {
+        street: "Main Street 1".to_string(),
+        city: "Berlin".to_string(),
+    }
+}
+
+#[test]
+fn multiple_schemas_order() {
+    let xml = include_str!("./xsd/multiple_schemas.xml");
+    let expected = schema::orders::Order {
+        id: "42".to_string(),
+        address: address(),
+    };
+    assert_eq!(schema::Order::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn multiple_schemas_invoice() {
+    let xml = include_str!("./xsd/multiple_schemas_invoice.xml");
+    let expected = schema::orders::Invoice {
+        amount: 10,
+        address: address(),
+    };
+    assert_eq!(schema::Invoice::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn multiple_schemas_namespaces() {
+    // the root of another namespace than the first schema is in the default namespace
+    let xml = include_str!("./xsd/multiple_schemas_shipment.xml");
+    let expected = schema::shipments::Shipment {
+        tracking: "T-1".to_string(),
+        order: schema::orders::Order {
+            id: "42".to_string(),
+            address: address(),
+        },
+    };
+    assert_eq!(schema::Shipment::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,22 @@
+#[xsd::all(
+    schema = "tests/xsd/namespace_modules.xsd",
+    module(namespace = "urn:billing", name = "invoicing")
+)]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn namespace_modules() {
+    let xml = include_str!("./xsd/namespace_modules.xml");
+    let expected = schema::customers::Customer {
+        billing: schema::invoicing::Address {
+            iban: "DE00123456780000000000".to_string(),
+        },
+        shipping: schema::shipping::Address {
+            city: "Berlin".to_string(),
+        },
+    };
+    assert_eq!(schema::Customer::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
@@ -0,0 +1,28 @@
+#[xsd::all(schema = "tests/xsd/override.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn override_() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<customer>
+  <address>
+    <city>Berlin</city>
+    <location lat="52.5" lon="13.4" />
+  </address>
+  <note>42</note>
+</customer>"#;
+    let expected = schema::Customer {
+        address: schema::Address {
+            city: "Berlin".to_string(),
+            location: schema::AddressLocationData {
+                lat: 52.5,
+                lon: 13.4,
+            },
+        },
+        note: 42,
+    };
+    assert_eq!(schema::Customer::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
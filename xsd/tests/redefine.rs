@@ -0,0 +1,30 @@
+#[xsd::all(schema = "tests/xsd/redefine.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn redefine() {
+    let xml = include_str!("./xsd/redefine.xml");
+    let expected = schema::Customer {
+        id: "42".to_string(),
+        version: 2,
+        address: schema::Address {
+            base: schema::AddressBase {
+                street: "Main Street".to_string(),
+            },
+            address: schema::AddressAddress {
+                city: "Berlin".to_string(),
+            },
+        },
+        code: schema::Code::new("ABC".to_string()).unwrap(),
+        // an existing type named like the original of a redefined type is kept
+        scheme: schema::CodeBase::Legacy,
+    };
+    assert_eq!(schema::Customer::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+
+    // the redefined simple type narrows the original one
+    let xml = xml.replace("ABC", "ABCD");
+    assert!(schema::Customer::from_xml(xml).is_err());
+}
@@ -0,0 +1,39 @@
+#[xsd::all(schema = "tests/xsd/simple_content_restriction.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::decode::FromXmlError;
+
+#[test]
+fn simple_content_restriction() {
+    let xml = include_str!("./xsd/simple_content_restriction.xml");
+    let expected = schema::Payment {
+        amount: schema::SmallAmount {
+            small_amount: 42,
+            currency: schema::Currency::Eur,
+        },
+        fee: schema::Fee {
+            fee_value: schema::FeeValue::V10,
+            currency: "USD".to_string(),
+            rounded: Some(true),
+        },
+    };
+    assert_eq!(schema::Payment::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn simple_content_restriction_invalid() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<payment>
+  <amount currency="EUR">500</amount>
+  <fee currency="USD">10</fee>
+</payment>"#;
+    assert!(matches!(
+        schema::Payment::from_xml(xml),
+        Err(FromXmlError::Restriction {
+            facet: "maxInclusive",
+            ..
+        })
+    ));
+}
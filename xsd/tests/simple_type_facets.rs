@@ -0,0 +1,93 @@
+#[xsd::all(schema = "tests/xsd/simple_type_facets.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::decode::FromXmlError;
+use xsd::restriction::RestrictionError;
+
+#[test]
+fn simple_type_facets() {
+    let xml = include_str!("./xsd/simple_type_facets.xml");
+    let expected = schema::Order {
+        code: schema::Code::new("ABC".to_string()).unwrap(),
+        discount: schema::Percent::new(15).unwrap(),
+        currency: schema::OrderCurrencyData::new("EUR".to_string()).unwrap(),
+    };
+    assert_eq!(schema::Order::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn simple_type_facets_violated() {
+    let cases = [
+        ("ABCDE", "15", "EUR", "maxLength"),
+        ("A", "15", "EUR", "minLength"),
+        ("abc", "15", "EUR", "pattern"),
+        ("ABC", "101", "EUR", "maxInclusive"),
+        ("ABC", "-1", "EUR", "minInclusive"),
+        ("ABC", "15", "EURO", "length"),
+    ];
+    for (code, discount, currency, expected_facet) in cases {
+        let xml = format!(
+            r#"<order currency="{currency}"><code>{code}</code><discount>{discount}</discount></order>"#
+        );
+        match schema::Order::from_xml(xml) {
+            Err(FromXmlError::Restriction { facet, .. }) => assert_eq!(facet, expected_facet),
+            result => panic!("expected {expected_facet} violation, got {result:?}"),
+        }
+    }
+}
+
+#[test]
+fn simple_type_facets_from_str() {
+    assert_eq!(
+        "AB".parse::<schema::Code>().unwrap(),
+        schema::Code::new("AB".to_string()).unwrap()
+    );
+    assert!(matches!(
+        "ab".parse::<schema::Code>(),
+        Err(FromXmlError::Restriction {
+            facet: "pattern",
+            ..
+        })
+    ));
+    assert!(matches!(
+        "1000".parse::<schema::Percent>(),
+        Err(FromXmlError::Restriction { .. })
+    ));
+}
+
+#[test]
+fn simple_type_facets_new() {
+    let code = schema::Code::new("ABCD".to_string()).unwrap();
+    assert_eq!(code.as_ref(), "ABCD");
+    assert_eq!(code.into_inner(), "ABCD");
+    assert!(matches!(
+        schema::Code::try_from("ABCDE".to_string()),
+        Err(RestrictionError {
+            facet: "maxLength",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn simple_type_facets_field_types() {
+    // fields of restricted types can only hold valid values
+    assert!(schema::Percent::new(150).is_err());
+    assert!(schema::OrderCurrencyData::new("EURO".to_string()).is_err());
+
+    let order = schema::Order {
+        code: "AB".parse().unwrap(),
+        discount: "100".parse().unwrap(),
+        currency: "USD".parse().unwrap(),
+    };
+    assert_eq!(
+        String::from_utf8_lossy(&order.to_xml().unwrap()),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<order currency="USD">
+  <code>AB</code>
+  <discount>100</discount>
+</order>"#
+    );
+}
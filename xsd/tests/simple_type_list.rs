@@ -0,0 +1,68 @@
+#[xsd::all(schema = "tests/xsd/simple_type_list.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::decode::FromXmlError;
+use xsd::restriction::RestrictionError;
+
+#[test]
+fn simple_type_list() {
+    let xml = include_str!("./xsd/simple_type_list.xml");
+    let expected = schema::Point {
+        pos: schema::Coordinates::new(vec![52.52, 13.405]).unwrap(),
+        flags: Some(schema::Flags(vec![
+            schema::FlagsItem::Visible,
+            schema::FlagsItem::Locked,
+        ])),
+        size: Some(schema::Size::new(vec![1, 2, 3]).unwrap()),
+        codes: Some(schema::Codes::new(vec!["AB".to_string(), "CD".to_string()]).unwrap()),
+    };
+    assert_eq!(schema::Point::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn simple_type_list_length() {
+    let xml = r#"<point><pos>52.52
+        13.405 34</pos></point>"#;
+    assert!(matches!(
+        schema::Point::from_xml(xml),
+        Err(FromXmlError::Restriction {
+            facet: "length",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn simple_type_list_pattern() {
+    // the pattern applies to the whole list
+    assert!("AB CD".parse::<schema::Codes>().is_ok());
+    assert!(matches!(
+        "AB cd".parse::<schema::Codes>(),
+        Err(FromXmlError::Restriction {
+            facet: "pattern",
+            ..
+        })
+    ));
+    assert!(matches!(
+        schema::Codes::new(vec!["AB".to_string(), String::new()]),
+        Err(RestrictionError {
+            facet: "pattern",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn simple_type_list_named_base() {
+    // the length of a restricted list counts its items
+    assert!("10 20 30".parse::<schema::Size>().is_ok());
+    assert!(matches!(
+        "10 20".parse::<schema::Size>(),
+        Err(FromXmlError::Restriction {
+            facet: "length",
+            ..
+        })
+    ));
+}
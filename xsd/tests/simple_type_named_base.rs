@@ -0,0 +1,59 @@
+#[xsd::all(schema = "tests/xsd/simple_type_named_base.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+use xsd::decode::FromXmlError;
+
+#[test]
+fn simple_type_named_base() {
+    let xml = include_str!("./xsd/simple_type_named_base.xml");
+    let expected = schema::Item {
+        price: schema::SmallPrice::new(42.5).unwrap(),
+        color: schema::Primary::Blue,
+        discount: Some(schema::SmallPrice::new(5.5).unwrap()),
+    };
+    assert_eq!(schema::Item::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn simple_type_named_base_inherited_facets() {
+    for (price, expected_facet) in [("150", "maxInclusive"), ("-1", "minInclusive")] {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<item>
+  <price>{price}</price>
+  <color>red</color>
+</item>"#
+        );
+        match schema::Item::from_xml(&xml) {
+            Err(FromXmlError::Restriction { facet, .. }) => assert_eq!(facet, expected_facet),
+            result => panic!("expected {expected_facet} violation, got {result:?}"),
+        }
+    }
+
+    assert!(schema::SmallPrice::new(50.0).is_ok());
+    assert!(schema::SmallPrice::new(150.0).is_err());
+    assert!(schema::SmallPrice::new(-1.0).is_err());
+}
+
+#[test]
+fn simple_type_named_base_subset_enum() {
+    assert_eq!(
+        schema::Color::from(schema::Primary::Red),
+        schema::Color::Red
+    );
+    assert_eq!(
+        schema::Primary::try_from(schema::Color::Blue).unwrap(),
+        schema::Primary::Blue
+    );
+    assert!(schema::Primary::try_from(schema::Color::Green).is_err());
+    assert!(schema::Item::from_xml(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<item>
+  <price>1</price>
+  <color>green</color>
+</item>"#
+    )
+    .is_err());
+}
@@ -0,0 +1,24 @@
+#[xsd::all(schema = "tests/xsd/simple_type_union.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn simple_type_union() {
+    let xml = include_str!("./xsd/simple_type_union.xml");
+    let expected = schema::Shirt {
+        delivery: vec![
+            schema::OptionalDate::Date("2024-01-31".to_string()),
+            schema::OptionalDate::Variant1(schema::OptionalDateVariant1::Variant1),
+        ],
+        size: schema::Size::SizeName(schema::SizeName::M),
+    };
+    assert_eq!(schema::Shirt::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}
+
+#[test]
+fn simple_type_union_member_order() {
+    assert_eq!("42".parse::<schema::Size>().unwrap(), schema::Size::Int(42));
+    assert!("XL".parse::<schema::Size>().is_err());
+}
@@ -0,0 +1,18 @@
+#[xsd::all(schema = "tests/xsd/simple_type_union_names.xsd")]
+mod schema {}
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn simple_type_union_names() {
+    let xml = include_str!("./xsd/simple_type_union_names.xml");
+    let expected = schema::main::Item {
+        code: vec![
+            schema::main::AnyCode::Code(42),
+            schema::main::AnyCode::Code2(schema::other::Code::new("ABC".to_string()).unwrap()),
+        ],
+        text: schema::main::Text::String("Hello".to_string()),
+    };
+    assert_eq!(schema::main::Item::from_xml(xml).unwrap(), expected);
+    assert_eq!(String::from_utf8_lossy(&expected.to_xml().unwrap()), xml);
+}